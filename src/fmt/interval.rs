@@ -1,6 +0,0 @@
-mod display;
-
-use core::time::Duration;
-
-#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub(crate) struct PgInterval(pub(crate) Duration);
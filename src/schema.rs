@@ -4,30 +4,82 @@
 //! [`Deletable`](clinvoice_adapter::Deletable)) for a Postgres filesystem.
 
 mod contact;
+mod count;
 mod employee;
+mod exchange_rates;
 mod expenses;
+mod explain;
+mod indexes;
 mod initializable;
 mod job;
 mod location;
+mod migrations;
 mod organization;
+mod page;
+mod period;
+mod reports;
+mod search;
+mod sort;
+mod str_comparison;
 mod timesheet;
+mod timesheet_overlap;
+mod trace;
+mod trigram;
 mod util;
+mod verify;
 mod write_where_clause;
 
+use std::time::Instant;
+
 use clinvoice_adapter::{
 	fmt::{sql, As, ColumnsToSql, QueryBuilderExt, SnakeCase, TableToSql},
 	WriteWhereClause,
 };
 use clinvoice_match::Match;
-use clinvoice_schema::Id;
+use clinvoice_schema::{chrono::NaiveDate, Id};
 pub use contact::PgContact;
-pub use employee::PgEmployee;
-pub use expenses::PgExpenses;
-pub use job::PgJob;
+pub use count::Countable;
+pub use employee::{EmployeeHours, OpenTimesheets, PgEmployee};
+pub use exchange_rates::{
+	EcbExchangeRates,
+	ExchangeRatesSource,
+	PgExchangeRates,
+	StaticExchangeRates,
+};
+pub use expenses::{ExpenseTotal, PgExpenses};
+pub use explain::{BoundArgument, Explainable, RenderedQuery};
+pub use indexes::{IndexReport, UnindexedForeignKey, UnusedIndex};
+pub use job::{BillableHours, InvoiceLineItem, InvoiceSummary, PgJob, Revenue, RevenueDate};
 pub use location::PgLocation;
-pub use organization::PgOrganization;
-use sqlx::{Executor, Postgres, QueryBuilder, Result, Transaction};
+use money2::{Currency, Decimal, Exchange, ExchangeRates};
+pub use organization::{AgedReceivables, PgOrganization};
+pub use page::{Page, Paginatable, Pagination};
+pub use period::Period;
+pub use search::Searchable;
+pub use sort::{
+	ContactSortKey,
+	Direction,
+	EmployeeSortKey,
+	ExpenseSortKey,
+	JobSortKey,
+	LocationSortKey,
+	OrganizationSortKey,
+	Sort,
+	Sortable,
+	TimesheetSortKey,
+};
+use sqlx::{Acquire, Executor, Pool, Postgres, QueryBuilder, Result, Transaction};
+pub use str_comparison::{StrComparable, StrComparison};
 pub use timesheet::PgTimesheet;
+pub use timesheet_overlap::OverlappingTimesheet;
+pub use verify::{
+	ColumnDefinition,
+	ColumnDrift,
+	ConstraintDrift,
+	DomainDrift,
+	IndexDrift,
+	SchemaDrift,
+};
 
 /// The struct which implements several [`clinvoice_adapter`] traits to allow CLInvoice to function
 /// within a Postgres database environment.
@@ -35,8 +87,16 @@ pub struct PgSchema;
 
 impl PgSchema
 {
-	/// Via `connection`, execute `DELETE FROM {table} WHERE (id = №) OR … OR (id = №)` for each
-	/// [`Id`] in `ids`.
+	/// Retrieve the version of the most recent migration which has been applied to the database
+	/// via `connection`, or [`None`] if the database has never been migrated.
+	pub async fn current_version<'connection, Conn>(connection: Conn) -> Result<Option<i64>>
+	where
+		Conn: Acquire<'connection, Database = Postgres>,
+	{
+		migrations::current_version(connection).await
+	}
+
+	/// Via `connection`, execute `DELETE FROM {table} WHERE id = ANY(…)` for the [`Id`]s in `ids`.
 	async fn delete<'args, Conn, Iter, Table>(connection: Conn, ids: Iter) -> Result<()>
 	where
 		Conn: Executor<'args, Database = Postgres>,
@@ -61,11 +121,253 @@ impl PgSchema
 			&mut query,
 		);
 
-		query.prepare().execute(connection).await?;
+		let result = query.prepare().execute(connection).await?;
+		trace::rows(result.rows_affected().try_into().unwrap_or(usize::MAX));
 
 		Ok(())
 	}
 
+	/// Drop the constraint created by [`PgSchema::enable_timesheet_overlap_exclusion`], so that the
+	/// [`Timesheet`](clinvoice_schema::Timesheet)s of an
+	/// [`Employee`](clinvoice_schema::Employee) may overlap again.
+	pub async fn disable_timesheet_overlap_exclusion<'connection, Conn>(
+		connection: Conn,
+	) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		timesheet_overlap::disable(connection).await
+	}
+
+	/// Drop the indexes created by [`PgSchema::enable_trigram_search`].
+	pub async fn disable_trigram_search<'connection, Conn>(connection: Conn) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		trigram::disable(connection).await
+	}
+
+	/// Create whatever the `comparison` needs (e.g. the `unaccent` extension, and the indexes which
+	/// keep the comparison fast) via `connection`, if it does not already exist. Btree expression
+	/// indexes serve [`MatchStr::EqualTo`](clinvoice_match::MatchStr::EqualTo), and trigram
+	/// indexes serve [`MatchStr::Contains`](clinvoice_match::MatchStr::Contains) and
+	/// [`MatchStr::Regex`](clinvoice_match::MatchStr::Regex).
+	///
+	/// The `comparison` is used by passing it to [`StrComparable::retrieve_comparing`].
+	pub async fn enable_str_comparison<'connection, Conn>(
+		connection: Conn,
+		comparison: StrComparison,
+	) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		str_comparison::setup(connection, comparison).await
+	}
+
+	/// Install the `btree_gist` extension (via `connection`) and create an exclusion constraint, so
+	/// that no two [`Timesheet`]s of the same [`Employee`](clinvoice_schema::Employee) may overlap.
+	/// A [`Timesheet`] which has not ended yet overlaps everything after its
+	/// [`time_begin`](Timesheet::time_begin).
+	///
+	/// Once enabled, [`TimesheetAdapter::create`] and [`Updatable::update`] return an
+	/// [`OverlappingTimesheet`] instead of writing a [`Timesheet`] which would overlap another.
+	/// This is not enabled by default, since some teams do work in parallel. Safe to call more than
+	/// once.
+	///
+	/// So that the overlapped [`Timesheet`] can be found, every [`Timesheet`] is written in a
+	/// savepoint of the transaction, which costs two more round trips to the database per write.
+	/// This is so whether or not overlap exclusion is enabled.
+	///
+	/// # Errors
+	///
+	/// * If the `btree_gist` extension is not available, or the user is not allowed to install it.
+	/// * If some [`Timesheet`]s already overlap.
+	///
+	/// [`Timesheet`]: clinvoice_schema::Timesheet
+	/// [`TimesheetAdapter::create`]: clinvoice_adapter::schema::TimesheetAdapter::create
+	/// [`Updatable::update`]: clinvoice_adapter::Updatable::update
+	pub async fn enable_timesheet_overlap_exclusion<'connection, Conn>(
+		connection: Conn,
+	) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		timesheet_overlap::enable(connection).await
+	}
+
+	/// Install the `pg_trgm` extension (via `connection`) and create GIN trigram indexes on the
+	/// text columns of every table (e.g. `timesheets.work_notes`), so that
+	/// [`MatchStr::Contains`](clinvoice_match::MatchStr::Contains) and
+	/// [`MatchStr::Regex`](clinvoice_match::MatchStr::Regex) do not have to scan whole tables.
+	///
+	/// This is done automatically by [`Initializable::init`](clinvoice_adapter::Initializable) when
+	/// the `trigram` feature is enabled. Safe to call more than once.
+	///
+	/// # Errors
+	///
+	/// * If the `pg_trgm` extension is not available, or the user is not allowed to install it.
+	pub async fn enable_trigram_search<'connection, Conn>(connection: Conn) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		trigram::enable(connection).await
+	}
+
+	/// Exchange the `value` into the given `currency`, using the [`ExchangeRates`] which the
+	/// `source` reports were effective on the given `date`.
+	///
+	/// # See also
+	///
+	/// * [`PgJob::exchange`], [`PgTimesheet::exchange`], and [`PgExpenses::exchange`], which pick
+	///   the `date` based on the entity being exchanged.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+	pub async fn exchange<T>(
+		connection: &Pool<Postgres>,
+		source: &dyn ExchangeRatesSource,
+		value: T,
+		currency: Currency,
+		date: NaiveDate,
+	) -> Result<T>
+	where
+		T: Exchange,
+	{
+		let rates = Self::exchange_rates(connection, source, date).await?;
+		Ok(value.exchange(currency, &rates))
+	}
+
+	/// Retrieve the [`ExchangeRates`] which were effective on the given `date` from the `source`,
+	/// using `connection` if necessary.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(exchange_rates_us)))]
+	pub async fn exchange_rates(
+		connection: &Pool<Postgres>,
+		source: &dyn ExchangeRatesSource,
+		date: NaiveDate,
+	) -> Result<ExchangeRates>
+	{
+		let start = Instant::now();
+		let rates = source.exchange_rates(connection, date).await;
+		trace::exchange_rates(start.elapsed());
+
+		rates
+	}
+
+	/// Import the historical exchange rates published by the European Central Bank (i.e. the
+	/// contents of `eurofxref-hist.csv`) into the `exchange_rates` table used by
+	/// [`PgExchangeRates`].
+	///
+	/// Rates which are `N/A`, or for a [`Currency`] which is not supported, are skipped.
+	pub async fn import_ecb_exchange_rates<'connection, Conn>(
+		connection: Conn,
+		csv: &str,
+	) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		exchange_rates::import(connection, exchange_rates::parse_ecb_history(csv)?).await
+	}
+
+	/// Import the `rate` of each [`Currency`] (relative to [`Currency::Eur`]) on each date into the
+	/// `exchange_rates` table used by [`PgExchangeRates`].
+	///
+	/// Any rate already stored for the same [`Currency`] and date is replaced.
+	pub async fn import_exchange_rates<'connection, Conn, Iter>(
+		connection: Conn,
+		rates: Iter,
+	) -> Result<()>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+		Iter: IntoIterator<Item = (NaiveDate, Currency, Decimal)>,
+	{
+		exchange_rates::import(connection, rates).await
+	}
+
+	/// Report the indexes of the database (via `connection`) which are missing, and those which
+	/// are unused.
+	///
+	/// Whether an index is unused is based on the statistics which Postgres collects, so it is
+	/// only meaningful once the database has been used for some time since they were last reset.
+	/// The database is not modified.
+	///
+	/// # Errors
+	///
+	/// * If the user is not allowed to create a schema in the database (i.e. it lacks the `CREATE`
+	///   privilege), since the indexes which should exist are found as in [`PgSchema::verify`].
+	pub async fn index_report<'connection, Conn>(connection: Conn) -> Result<IndexReport>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		indexes::report(connection).await
+	}
+
+	/// Apply every migration which has not yet been applied to the database via `connection`.
+	///
+	/// Safe to call more than once, and from more than one process at a time.
+	///
+	/// # See also
+	///
+	/// * [`PgSchema::migrate_to`]
+	pub async fn migrate<'connection, Conn>(connection: Conn) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		migrations::migrate_to(connection, migrations::latest_version()).await
+	}
+
+	/// Apply (or revert) migrations via `connection` until the database schema is at `version`. A
+	/// `version` of `0` reverts every migration.
+	///
+	/// All of the migrations are applied in a single transaction, so either every one succeeds or
+	/// the database is left untouched.
+	///
+	/// # Errors
+	///
+	/// * [`MigrateError::VersionMissing`](sqlx::migrate::MigrateError::VersionMissing) if there is
+	///   no migration with the given `version`, or the database was migrated by a newer version of
+	///   this crate.
+	pub async fn migrate_to<'connection, Conn>(connection: Conn, version: i64) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		migrations::migrate_to(connection, version).await
+	}
+
+	/// Compare the tables, columns, constraints, domains, and indexes of the database (via
+	/// `connection`) to those which [`PgSchema::migrate`] would create, and report any differences.
+	///
+	/// The database is not modified. Use this to check databases which may have been edited by
+	/// hand before using them.
+	///
+	/// # Errors
+	///
+	/// * If the user is not allowed to create a schema in the database (i.e. it lacks the `CREATE`
+	///   privilege). What [`PgSchema::migrate`] would create is found by migrating a temporary
+	///   schema, which is then rolled back.
+	pub async fn verify<'connection, Conn>(connection: Conn) -> Result<SchemaDrift>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		verify::verify(connection).await
+	}
+
+	/// Refresh the materialized views (via `connection`) which summarize the database for reports:
+	///
+	/// * `report_client_revenue_by_month`, the revenue from each client in each month that it was
+	///   invoiced.
+	/// * `report_job_hours_by_day`, the hours logged for each job on each day.
+	///
+	/// The views are created by [`PgSchema::migrate`], and only change when they are refreshed.
+	/// When `concurrently`, they may still be read while they are being refreshed, at the cost of
+	/// a slower refresh.
+	pub async fn refresh_reports<'connection, Conn>(
+		connection: Conn,
+		concurrently: bool,
+	) -> Result<()>
+	where
+		Conn: Acquire<'connection, Database = Postgres> + Send,
+	{
+		reports::refresh(connection, concurrently).await
+	}
+
 	/// Execute a query over the given `connection` which updates `columns` of a `table` given
 	/// the some values specified by `push_values` (e.g.
 	/// `|query| query.push_values(my_iterator, |mut q, value| …)`).
@@ -107,6 +409,63 @@ impl PgSchema
 
 		columns.push_update_where_to(&mut query, Columns::DEFAULT_ALIAS, values_alias);
 
+		let result = query.prepare().execute(connection).await?;
+		trace::rows(result.rows_affected().try_into().unwrap_or(usize::MAX));
+
+		Ok(())
+	}
+
+	/// Execute a query over the given `connection` which sets the `column` of the `Table` to the
+	/// [`Currency`] paired with each [`Id`] in `currencies`.
+	///
+	/// This is for the columns which store the [`Currency`] of some [`Money`](money2::Money), as
+	/// they are not part of any [`ColumnsToSql`] implementor and so cannot be set by
+	/// [`PgSchema::update`].
+	async fn update_currency<Iter, Table>(
+		connection: &mut Transaction<'_, Postgres>,
+		column: &str,
+		currencies: Iter,
+	) -> Result<()>
+	where
+		Iter: Iterator<Item = (Id, Currency)>,
+		Table: TableToSql,
+	{
+		let mut peekable_currencies = currencies.peekable();
+
+		// There is nothing to do.
+		if peekable_currencies.peek().is_none()
+		{
+			return Ok(());
+		}
+
+		let values_alias = SnakeCase::from((Table::DEFAULT_ALIAS, 'V'));
+		let mut query = QueryBuilder::new(sql::UPDATE);
+
+		query
+			.push(As(Table::TABLE_NAME, Table::DEFAULT_ALIAS))
+			.push(sql::SET)
+			.push(column)
+			.push('=')
+			.push(values_alias)
+			.push('.')
+			.push(column)
+			.push(sql::FROM)
+			.push('(')
+			.push_values(peekable_currencies, |mut q, (id, currency)| {
+				q.push_bind(id).push_bind(currency.to_string());
+			})
+			.push(')')
+			.push(sql::AS)
+			.push(values_alias)
+			.push(" (id,")
+			.push(column)
+			.push(')')
+			.push(sql::WHERE)
+			.push(Table::DEFAULT_ALIAS)
+			.push(".id =")
+			.push(values_alias)
+			.push(".id");
+
 		query.prepare().execute(connection).await?;
 
 		Ok(())
@@ -3,10 +3,11 @@ mod deletable;
 mod retrievable;
 mod updatable;
 
+use std::collections::HashMap;
+
 use clinvoice_adapter::schema::columns::ContactColumns;
-use clinvoice_schema::{Contact, ContactKind};
-use futures::TryFutureExt;
-use sqlx::{postgres::PgRow, Error, Executor, Postgres, Result, Row};
+use clinvoice_schema::{Contact, ContactKind, Id, Location};
+use sqlx::{postgres::PgRow, Error, Result, Row};
 
 use super::PgLocation;
 
@@ -16,22 +17,19 @@ pub struct PgContact;
 
 impl PgContact
 {
-	pub(super) async fn row_to_view<'connection, Conn>(
-		connection: Conn,
+	/// Construct a [`Contact`] from the `row`, whose address (if any) must be among the
+	/// `locations`.
+	pub(super) fn row_to_view(
 		columns: ContactColumns<&str>,
+		locations: &HashMap<Id, Location>,
 		row: &PgRow,
 	) -> Result<Contact>
-	where
-		Conn: Executor<'connection, Database = Postgres>,
 	{
 		Ok(Contact {
 			label: row.get(columns.label),
 			kind:  match row.get::<Option<_>, _>(columns.address_id)
 			{
-				Some(id) =>
-				{
-					PgLocation::retrieve_by_id(connection, id).map_ok(ContactKind::Address).await?
-				},
+				Some(id) => PgLocation::get(locations, id).map(ContactKind::Address)?,
 				_ => row
 					.get::<Option<_>, _>(columns.email)
 					.map(ContactKind::Email)
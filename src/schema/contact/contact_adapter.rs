@@ -7,6 +7,7 @@ use super::PgContact;
 #[async_trait::async_trait]
 impl ContactAdapter for PgContact
 {
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Contact")))]
 	async fn create<'connection, Conn>(
 		connection: Conn,
 		kind: ContactKind,
@@ -16,6 +16,10 @@ impl Deletable for PgContact
 	type Db = Postgres;
 	type Entity = Contact;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Contact", rows))
+	)]
 	async fn delete<'connection, 'entity, Conn, Iter>(
 		connection: Conn,
 		entities: Iter,
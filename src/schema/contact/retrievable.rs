@@ -5,11 +5,91 @@ use clinvoice_adapter::{
 };
 use clinvoice_match::MatchContact;
 use clinvoice_schema::Contact;
-use futures::TryStreamExt;
-use sqlx::{Pool, Postgres, QueryBuilder, Result};
+use sqlx::{postgres::PgRow, Error, Pool, Postgres, QueryBuilder, Result, Row};
 
 use super::PgContact;
-use crate::schema::write_where_clause;
+use crate::schema::{
+	count,
+	explain,
+	sort,
+	trace,
+	write_where_clause,
+	ContactSortKey,
+	Countable,
+	Explainable,
+	Page,
+	Paginatable,
+	Pagination,
+	PgLocation,
+	RenderedQuery,
+	Sort,
+	Sortable,
+	StrComparable,
+	StrComparison,
+};
+
+const COLUMNS: ContactColumns<&'static str> = ContactColumns::default();
+
+/// Generate the query which retrieves all [`Contact`]s that match the `match_condition`.
+///
+/// `connection` is used to retrieve the [`Location`](clinvoice_schema::Location)s which match
+/// [`MatchContactKind::Address`](clinvoice_match::MatchContactKind::Address).
+async fn query<'match_condition>(
+	connection: &Pool<Postgres>,
+	match_condition: &'match_condition MatchContact,
+	comparison: StrComparison,
+) -> Result<QueryBuilder<'match_condition, Postgres>>
+{
+	let mut query = QueryBuilder::new(sql::SELECT);
+
+	query.push_columns(&COLUMNS.default_scope());
+	push_from_where(connection, &mut query, match_condition, comparison).await?;
+
+	Ok(query)
+}
+
+/// Write the `FROM` and `WHERE` clauses which select the [`Contact`]s that match the
+/// `match_condition` to the `query`.
+///
+/// `connection` is used the same way as in [`query`].
+async fn push_from_where(
+	connection: &Pool<Postgres>,
+	query: &mut QueryBuilder<'_, Postgres>,
+	match_condition: &MatchContact,
+	comparison: StrComparison,
+) -> Result<()>
+{
+	query.push_default_from::<ContactColumns<char>>();
+
+	write_where_clause::write_match_contact(
+		connection,
+		comparison,
+		Default::default(),
+		ContactColumns::<char>::DEFAULT_ALIAS,
+		match_condition,
+		query,
+	)
+	.await?;
+
+	Ok(())
+}
+
+/// The (scoped) column which [`Contact`]s are sorted by for the `key`.
+fn sort_column(key: ContactSortKey) -> String
+{
+	match key
+	{
+		ContactSortKey::Label => COLUMNS.default_scope().label.to_string(),
+	}
+}
+
+/// Construct a [`Contact`] from each of the `rows`.
+async fn rows_to_views(connection: &Pool<Postgres>, rows: &[PgRow]) -> Result<Vec<Contact>>
+{
+	trace::rows(rows.len());
+	let locations = PgLocation::retrieve_for_rows(connection, COLUMNS.address_id, rows).await?;
+	rows.iter().map(|row| PgContact::row_to_view(COLUMNS, &locations, row)).collect()
+}
 
 /// Implementors of this trait are capable of being retrieved from a [`Database`].
 #[async_trait::async_trait]
@@ -28,26 +108,143 @@ impl Retrievable for PgContact
 		match_condition: Self::Match,
 	) -> Result<Vec<Self::Entity>>
 	{
-		const COLUMNS: ContactColumns<&'static str> = ContactColumns::default();
+		Self::retrieve_comparing(connection, match_condition, StrComparison::Exact).await
+	}
+}
+
+#[async_trait::async_trait]
+impl StrComparable for PgContact
+{
+	/// Retrieve all [`Contact`]s (via `connection`) that match the `match_condition`, comparing
+	/// strings according to the `comparison`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Contact", locations, rows))
+	)]
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let mut query = query(connection, &match_condition, comparison).await?;
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Paginatable for PgContact
+{
+	/// Retrieve the [`Page`] of [`Contact`]s (via `connection`) that match the `match_condition`
+	/// which is described by the `pagination`.
+	///
+	/// # Errors
+	///
+	/// * [`Error::ColumnNotFound`] unless the `pagination` is [`Pagination::Offset`], since
+	///   [`Contact`]s do not have an `id`. They are ordered by their `label` instead.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Contact", locations, rows))
+	)]
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>
+	{
+		if !matches!(pagination, Pagination::Offset { .. })
+		{
+			return Err(Error::ColumnNotFound("id".into()));
+		}
+
+		let mut query = query(connection, &match_condition, StrComparison::Exact).await?;
+		pagination.push_order_limit(&mut query, COLUMNS.default_scope().label, None)?;
 
+		let mut rows = query.prepare().fetch_all(connection).await?;
+		let next = pagination.next(&mut rows, COLUMNS.label, None)?;
+
+		Ok(Page { entities: rows_to_views(connection, &rows).await?, next })
+	}
+}
+
+#[async_trait::async_trait]
+impl Sortable for PgContact
+{
+	type SortKey = ContactSortKey;
+
+	/// Retrieve all [`Contact`]s (via `connection`) that match the `match_condition`, ordered by
+	/// the `sort`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Contact", locations, rows))
+	)]
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>
+	{
+		let mut query = query(connection, &match_condition, StrComparison::Exact).await?;
+
+		// NOTE: `label` is the primary key of `contact_information`, so it breaks all ties.
+		sort::push_order_by(&mut query, sort, sort_column, COLUMNS.default_scope().label);
+
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Countable for PgContact
+{
+	/// Count the [`Contact`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Contact")))]
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>
+	{
 		let mut query = QueryBuilder::new(sql::SELECT);
 
-		query.push_columns(&COLUMNS.default_scope()).push_default_from::<ContactColumns<char>>();
-
-		write_where_clause::write_match_contact(
-			connection,
-			Default::default(),
-			ContactColumns::<char>::DEFAULT_ALIAS,
-			&match_condition,
-			&mut query,
-		)
-		.await?;
-
-		query
-			.prepare()
-			.fetch(connection)
-			.and_then(|row| async move { Self::row_to_view(connection, COLUMNS, &row).await })
-			.try_collect()
-			.await
+		query.push(count::COUNT);
+		push_from_where(connection, &mut query, &match_condition, StrComparison::Exact).await?;
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+
+	/// Check whether there are any [`Contact`]s (via `connection`) that match the
+	/// `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Contact")))]
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>
+	{
+		let mut query = QueryBuilder::new(sql::SELECT);
+
+		query.push(count::EXISTS);
+		push_from_where(connection, &mut query, &match_condition, StrComparison::Exact).await?;
+		query.push(')');
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+}
+
+#[async_trait::async_trait]
+impl Explainable for PgContact
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the query which retrieves the
+	/// [`Contact`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Contact")))]
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>
+	{
+		let mut query = query(connection, &match_condition, StrComparison::Exact).await?;
+		explain::explain(connection, &mut query).await
+	}
+
+	/// Render the query which retrieves the [`Contact`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Contact")))]
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>
+	{
+		let mut query = query(connection, &match_condition, StrComparison::Exact).await?;
+		explain::render(connection, &mut query).await
 	}
 }
@@ -11,6 +11,10 @@ impl Updatable for PgContact
 	type Db = Postgres;
 	type Entity = Contact;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Contact", rows))
+	)]
 	async fn update<'entity, Iter>(
 		connection: &mut Transaction<Self::Db>,
 		entities: Iter,
@@ -0,0 +1,32 @@
+//! # Summary
+//!
+//! This module contains a trait which allows the results of a [`Retrievable`] to be counted
+//! without retrieving them.
+
+use clinvoice_adapter::Retrievable;
+use sqlx::{Pool, Postgres, Result};
+
+/// The expression which counts the rows of a query.
+pub(super) const COUNT: &str = "count(*)";
+
+/// The start of the expression which checks whether a query has any rows. It must be closed with
+/// a `)` after the `WHERE` clause.
+pub(super) const EXISTS: &str = "EXISTS (SELECT 1";
+
+/// Implementors of this trait are capable of counting the entities in a
+/// [`Database`](sqlx::Database), without retrieving them.
+#[async_trait::async_trait]
+pub trait Countable: Retrievable<Db = Postgres>
+{
+	/// Count the [`Retrievable::Entity`]s (via `connection`) that match the `match_condition`.
+	///
+	/// Equivalent to (but much faster than) [`Retrievable::retrieve`] followed by
+	/// [`len`](Vec::len).
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>;
+
+	/// Check whether there are any [`Retrievable::Entity`]s (via `connection`) that match the
+	/// `match_condition`.
+	///
+	/// Equivalent to (but much faster than) [`Countable::count`] followed by `> 0`.
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>;
+}
@@ -1,11 +1,16 @@
 mod deletable;
 mod employee_adapter;
+mod hours;
 mod retrievable;
 mod updatable;
 
-use clinvoice_adapter::schema::columns::EmployeeColumns;
+use clinvoice_adapter::{fmt::sql, schema::columns::EmployeeColumns};
+use clinvoice_match::MatchEmployee;
 use clinvoice_schema::Employee;
-use sqlx::{postgres::PgRow, Row};
+pub use hours::{EmployeeHours, OpenTimesheets};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
+
+use super::{Period, StrComparison};
 
 /// Implementor of the [`EmployeeAdapter`](clinvoice_adapter::schema::EmployeeAdapter) for the
 /// [`Postgres`](sqlx::Postgres) database.
@@ -13,6 +18,37 @@ pub struct PgEmployee;
 
 impl PgEmployee
 {
+	/// Retrieve the [`EmployeeHours`] of every [`Employee`] that matches the `match_condition` in
+	/// each `period`, broken down by client and [`Job`](clinvoice_schema::Job).
+	///
+	/// How [`Timesheet`](clinvoice_schema::Timesheet)s which have not ended yet are counted is
+	/// decided by `open`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Employee", rows))
+	)]
+	pub async fn hours(
+		connection: &Pool<Postgres>,
+		match_condition: MatchEmployee,
+		period: Period,
+		open: OpenTimesheets,
+	) -> Result<Vec<EmployeeHours>>
+	{
+		hours::retrieve(connection, &match_condition, period, open).await
+	}
+
+	/// Write `(SELECT … FROM … WHERE …)`, which selects the [`Id`](clinvoice_schema::Id) of every
+	/// [`Employee`] that matches the `match_condition`, to the `query`.
+	pub(super) fn push_matching_ids(
+		query: &mut QueryBuilder<Postgres>,
+		match_condition: &MatchEmployee,
+	)
+	{
+		query.push('(').push(sql::SELECT).push(EmployeeColumns::default().default_scope().id);
+		retrievable::push_from_where(query, match_condition, StrComparison::Exact);
+		query.push(')');
+	}
+
 	pub(super) fn row_to_view<T>(columns: EmployeeColumns<T>, row: &PgRow) -> Employee
 	where
 		T: AsRef<str>,
@@ -11,6 +11,10 @@ impl Deletable for PgEmployee
 	type Db = Postgres;
 	type Entity = Employee;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Employee", rows))
+	)]
 	async fn delete<'connection, 'entity, Conn, Iter>(
 		connection: Conn,
 		entities: Iter,
@@ -35,6 +39,7 @@ mod tests
 {
 	use clinvoice_adapter::{schema::EmployeeAdapter, Deletable, Retrievable};
 	use clinvoice_match::Match;
+	use clinvoice_schema::{Employee, Id};
 	use pretty_assertions::assert_eq;
 
 	use crate::schema::{util, PgEmployee};
@@ -63,6 +68,11 @@ mod tests
 
 		PgEmployee::delete(&connection, [&employee, &employee2].into_iter()).await.unwrap();
 
+		// NOTE: more `Id`s than Postgres accepts bind parameters, none of which exist.
+		let missing: Vec<_> =
+			(Id::MAX - 70_000..Id::MAX).map(|id| Employee { id, ..employee3.clone() }).collect();
+		PgEmployee::delete(&connection, missing.iter()).await.unwrap();
+
 		assert_eq!(
 			PgEmployee::retrieve(
 				&connection,
@@ -7,6 +7,7 @@ use super::PgEmployee;
 #[async_trait::async_trait]
 impl EmployeeAdapter for PgEmployee
 {
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Employee")))]
 	async fn create<'connection, Conn>(
 		connection: Conn,
 		name: String,
@@ -0,0 +1,311 @@
+use clinvoice_adapter::fmt::QueryBuilderExt;
+use clinvoice_match::MatchEmployee;
+use clinvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+use money2::Decimal;
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
+
+use super::PgEmployee;
+use crate::schema::{trace, Period};
+
+/// How [`Timesheet`](clinvoice_schema::Timesheet)s which have not ended yet are counted in
+/// [`EmployeeHours`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum OpenTimesheets
+{
+	/// Open [`Timesheet`](clinvoice_schema::Timesheet)s are not counted at all.
+	Exclude,
+
+	/// Open [`Timesheet`](clinvoice_schema::Timesheet)s are counted as if they ended now.
+	UntilNow,
+}
+
+/// The hours that an [`Employee`](clinvoice_schema::Employee) logged in a single [`Period`].
+///
+/// When the [`organization_id`](Self::organization_id) is [`None`], these are the hours logged for
+/// every client. When the [`job_id`](Self::job_id) is [`None`], these are the hours logged for
+/// every [`Job`](clinvoice_schema::Job) of the client.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct EmployeeHours
+{
+	/// The [`Id`] of the [`Employee`](clinvoice_schema::Employee).
+	pub employee_id: Id,
+
+	/// The hours logged, without rounding.
+	pub hours: Decimal,
+
+	/// The [`Id`] of the [`Job`](clinvoice_schema::Job) which the hours were logged for.
+	pub job_id: Option<Id>,
+
+	/// How many of the [`timesheets`](Self::timesheets) have not ended yet.
+	pub open_timesheets: i64,
+
+	/// The [`Id`] of the client [`Organization`](clinvoice_schema::Organization) which the hours
+	/// were logged for.
+	pub organization_id: Option<Id>,
+
+	/// When the [`Period`] began.
+	pub period: DateTime<Utc>,
+
+	/// The number of [`Timesheet`](clinvoice_schema::Timesheet)s which the hours were logged in.
+	pub timesheets: i64,
+}
+
+impl EmployeeHours
+{
+	/// Construct [`EmployeeHours`] from the `row`.
+	fn from_row(row: &PgRow) -> Result<Self>
+	{
+		Ok(Self {
+			employee_id: row.try_get("employee_id")?,
+			hours: row.try_get("hours")?,
+			job_id: row.try_get("job_id")?,
+			open_timesheets: row.try_get("open_timesheets")?,
+			organization_id: row.try_get("organization_id")?,
+			period: row.try_get("period")?,
+			timesheets: row.try_get("timesheets")?,
+		})
+	}
+}
+
+/// Retrieve the [`EmployeeHours`] of each [`Employee`](clinvoice_schema::Employee) which matches
+/// the `match_condition`, in each `period`.
+///
+/// The hours of each [`Employee`](clinvoice_schema::Employee) in a `period` come first, followed
+/// by the hours of each client and then each of its [`Job`](clinvoice_schema::Job)s. A
+/// [`Timesheet`](clinvoice_schema::Timesheet) belongs to the `period` in which it began.
+pub(super) async fn retrieve(
+	connection: &Pool<Postgres>,
+	match_condition: &MatchEmployee,
+	period: Period,
+	open: OpenTimesheets,
+) -> Result<Vec<EmployeeHours>>
+{
+	let mut query = QueryBuilder::new(
+		"SELECT T.employee_id, P.period, J.client_id AS organization_id, T.job_id,
+			to_hours(sum(coalesce(T.time_end, now()) - T.time_begin)) AS hours,
+			count(*) AS timesheets,
+			count(*) FILTER (WHERE T.time_end IS NULL) AS open_timesheets
+		FROM timesheets T
+		JOIN jobs J ON (J.id = T.job_id)
+		CROSS JOIN LATERAL (SELECT ",
+	);
+
+	period.push_date_trunc(&mut query, "T.time_begin");
+	query.push(" AS period) P WHERE T.employee_id IN ");
+	PgEmployee::push_matching_ids(&mut query, match_condition);
+
+	if open == OpenTimesheets::Exclude
+	{
+		query.push(" AND T.time_end IS NOT NULL");
+	}
+
+	query.push(
+		" GROUP BY GROUPING SETS (
+			(T.employee_id, P.period),
+			(T.employee_id, P.period, J.client_id),
+			(T.employee_id, P.period, J.client_id, T.job_id)
+		)
+		ORDER BY T.employee_id, P.period, J.client_id NULLS FIRST, T.job_id NULLS FIRST;",
+	);
+
+	let rows = query.prepare().fetch_all(connection).await?;
+	trace::rows(rows.len());
+	rows.iter().map(EmployeeHours::from_row).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	};
+	use clinvoice_match::{Match, MatchEmployee};
+	use clinvoice_schema::{
+		chrono::{self, TimeZone, Utc},
+		Currency,
+		Invoice,
+		Money,
+	};
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+
+	use super::{EmployeeHours, OpenTimesheets};
+	use crate::{
+		schema::{util, Period, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn retrieve()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+
+		// NOTE: created one after the other, so that the first has the lower `Id`.
+		let organization =
+			PgOrganization::create(&connection, earth.clone(), "Some Organization".into())
+				.await
+				.unwrap();
+		let organization2 =
+			PgOrganization::create(&connection, earth, "Some Other Organizatión".into())
+				.await
+				.unwrap();
+
+		let (employee, employee2) = futures::try_join!(
+			PgEmployee::create(&connection, "My Name".into(), "Employed".into(), "Janitor".into()),
+			PgEmployee::create(
+				&connection,
+				"Another Gúy".into(),
+				"Employed".into(),
+				"Clerk".into()
+			),
+		)
+		.unwrap();
+
+		let (job, job2) = futures::try_join!(
+			PgJob::create(
+				&connection,
+				organization.clone(),
+				None,
+				Utc.ymd(2022, 08, 01).and_hms(08, 00, 00),
+				Duration::from_secs(900),
+				Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+				String::new(),
+				"Clean".into(),
+			),
+			PgJob::create(
+				&connection,
+				organization2.clone(),
+				None,
+				Utc.ymd(2022, 08, 01).and_hms(08, 00, 00),
+				Duration::from_secs(900),
+				Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+				String::new(),
+				"Tidy".into(),
+			),
+		)
+		.unwrap();
+
+		// {{{
+		let mut transaction = connection.begin().await.unwrap();
+
+		let now = Utc::now();
+		for (employee, job, time_begin, time_end) in [
+			// NOTE: 2022-08-01 is a Monday
+			(
+				&employee,
+				&job,
+				Utc.ymd(2022, 08, 01).and_hms(09, 00, 00),
+				Some(Utc.ymd(2022, 08, 01).and_hms(10, 30, 00)),
+			),
+			(
+				&employee,
+				&job2,
+				Utc.ymd(2022, 08, 03).and_hms(09, 00, 00),
+				Some(Utc.ymd(2022, 08, 03).and_hms(10, 00, 00)),
+			),
+			(
+				&employee,
+				&job,
+				Utc.ymd(2022, 08, 09).and_hms(09, 00, 00),
+				Some(Utc.ymd(2022, 08, 09).and_hms(11, 00, 00)),
+			),
+			(&employee, &job, now - chrono::Duration::hours(1), None),
+			(
+				&employee2,
+				&job,
+				Utc.ymd(2022, 08, 01).and_hms(09, 00, 00),
+				Some(Utc.ymd(2022, 08, 01).and_hms(17, 00, 00)),
+			),
+		]
+		{
+			PgTimesheet::create(
+				&mut transaction,
+				employee.clone(),
+				Vec::new(),
+				job.clone(),
+				time_begin,
+				time_end,
+				String::new(),
+			)
+			.await
+			.unwrap();
+		}
+
+		transaction.commit().await.unwrap();
+		// }}}
+
+		let match_condition = MatchEmployee { id: employee.id.into(), ..Default::default() };
+		let hours = |period, organization_id, job_id, hours, timesheets| EmployeeHours {
+			employee_id: employee.id,
+			hours: Decimal::new(hours, 1),
+			job_id,
+			open_timesheets: 0,
+			organization_id,
+			period,
+			timesheets,
+		};
+
+		let first_week = Utc.ymd(2022, 08, 01).and_hms(00, 00, 00);
+		let second_week = Utc.ymd(2022, 08, 08).and_hms(00, 00, 00);
+
+		assert_eq!(
+			PgEmployee::hours(
+				&connection,
+				match_condition.clone(),
+				Period::Week,
+				OpenTimesheets::Exclude,
+			)
+			.await
+			.unwrap(),
+			[
+				hours(first_week, None, None, 2_5, 2),
+				hours(first_week, Some(organization.id), None, 1_5, 1),
+				hours(first_week, Some(organization.id), Some(job.id), 1_5, 1),
+				hours(first_week, Some(organization2.id), None, 1_0, 1),
+				hours(first_week, Some(organization2.id), Some(job2.id), 1_0, 1),
+				hours(second_week, None, None, 2_0, 1),
+				hours(second_week, Some(organization.id), None, 2_0, 1),
+				hours(second_week, Some(organization.id), Some(job.id), 2_0, 1),
+			]
+		);
+
+		let until_now = PgEmployee::hours(
+			&connection,
+			MatchEmployee {
+				id: Match::Or(vec![employee.id.into(), employee2.id.into()]),
+				..Default::default()
+			},
+			Period::Year,
+			OpenTimesheets::UntilNow,
+		)
+		.await
+		.unwrap();
+
+		// NOTE: the open timesheet is counted in every row for the current year of `employee`
+		let open = until_now.iter().filter(|h| h.open_timesheets == 1).collect::<Vec<_>>();
+		assert_eq!(open.len(), 3);
+		assert!(open.iter().all(|h| h.employee_id == employee.id && h.hours >= Decimal::ONE));
+
+		assert!(until_now.contains(&EmployeeHours {
+			employee_id: employee2.id,
+			hours: Decimal::new(8, 0),
+			job_id: None,
+			open_timesheets: 0,
+			organization_id: None,
+			period: Utc.ymd(2022, 01, 01).and_hms(00, 00, 00),
+			timesheets: 1,
+		}));
+	}
+}
@@ -2,15 +2,80 @@ use clinvoice_adapter::{
 	fmt::{sql, QueryBuilderExt, TableToSql},
 	schema::columns::EmployeeColumns,
 	Retrievable,
-	WriteWhereClause,
+	WriteContext,
 };
 use clinvoice_match::MatchEmployee;
 use clinvoice_schema::Employee;
 use futures::TryStreamExt;
-use sqlx::{Pool, Postgres, QueryBuilder, Result};
+use sqlx::{Pool, Postgres, QueryBuilder, Result, Row};
 
 use super::PgEmployee;
-use crate::PgSchema;
+use crate::schema::{
+	count,
+	explain,
+	sort,
+	trace,
+	write_where_clause::WriteWhere,
+	Countable,
+	EmployeeSortKey,
+	Explainable,
+	Page,
+	Paginatable,
+	Pagination,
+	RenderedQuery,
+	Sort,
+	Sortable,
+	StrComparable,
+	StrComparison,
+};
+
+const COLUMNS: EmployeeColumns<&'static str> = EmployeeColumns::default();
+
+/// Generate the query which retrieves all [`Employee`]s that match the `match_condition`, and
+/// the [`WriteContext`] which follows its `WHERE` clause.
+fn query(
+	match_condition: &MatchEmployee,
+	comparison: StrComparison,
+) -> (QueryBuilder<Postgres>, WriteContext)
+{
+	let mut query = QueryBuilder::new(sql::SELECT);
+
+	query.push_columns(&COLUMNS.default_scope());
+	let context = push_from_where(&mut query, match_condition, comparison);
+
+	(query, context)
+}
+
+/// Write the `FROM` and `WHERE` clauses which select the [`Employee`]s that match the
+/// `match_condition` to the `query`, and return the [`WriteContext`] which follows them.
+pub(super) fn push_from_where(
+	query: &mut QueryBuilder<Postgres>,
+	match_condition: &MatchEmployee,
+	comparison: StrComparison,
+) -> WriteContext
+{
+	query.push_default_from::<EmployeeColumns<char>>();
+
+	comparison.write_where(
+		Default::default(),
+		EmployeeColumns::<char>::DEFAULT_ALIAS,
+		match_condition,
+		query,
+	)
+}
+
+/// The (scoped) column which [`Employee`]s are sorted by for the `key`.
+fn sort_column(key: EmployeeSortKey) -> String
+{
+	let columns = COLUMNS.default_scope();
+	match key
+	{
+		EmployeeSortKey::Id => columns.id.to_string(),
+		EmployeeSortKey::Name => columns.name.to_string(),
+		EmployeeSortKey::Status => columns.status.to_string(),
+		EmployeeSortKey::Title => columns.title.to_string(),
+	}
+}
 
 /// Implementors of this trait are capable of being retrieved from a [`Database`].
 #[async_trait::async_trait]
@@ -29,25 +94,152 @@ impl Retrievable for PgEmployee
 		match_condition: Self::Match,
 	) -> Result<Vec<Self::Entity>>
 	{
-		const COLUMNS: EmployeeColumns<&'static str> = EmployeeColumns::default();
+		Self::retrieve_comparing(connection, match_condition, StrComparison::Exact).await
+	}
+}
 
-		let mut query = QueryBuilder::new(sql::SELECT);
+#[async_trait::async_trait]
+impl StrComparable for PgEmployee
+{
+	/// Retrieve all [`Employee`]s (via `connection`) that match the `match_condition`, comparing
+	/// strings according to the `comparison`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Employee", rows))
+	)]
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, comparison);
 
-		query.push_columns(&COLUMNS.default_scope()).push_default_from::<EmployeeColumns<char>>();
+		let entities: Vec<_> = query
+			.prepare()
+			.fetch(connection)
+			.map_ok(|row| Self::row_to_view(COLUMNS, &row))
+			.try_collect()
+			.await?;
 
-		PgSchema::write_where_clause(
-			Default::default(),
-			EmployeeColumns::<char>::DEFAULT_ALIAS,
-			&match_condition,
-			&mut query,
-		);
+		trace::rows(entities.len());
+		Ok(entities)
+	}
+}
 
-		query
+#[async_trait::async_trait]
+impl Paginatable for PgEmployee
+{
+	/// Retrieve the [`Page`] of [`Employee`]s (via `connection`) that match the `match_condition`
+	/// which is described by the `pagination`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Employee", rows))
+	)]
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>
+	{
+		let columns = COLUMNS.default_scope();
+		let (mut query, context) = query(&match_condition, StrComparison::Exact);
+
+		pagination.push_where(&mut query, context, columns.id, None)?;
+		pagination.push_order_limit(&mut query, columns.id, None)?;
+
+		let mut rows = query.prepare().fetch_all(connection).await?;
+		let next = pagination.next(&mut rows, COLUMNS.id, None)?;
+		trace::rows(rows.len());
+
+		let entities = rows.iter().map(|row| Self::row_to_view(COLUMNS, row)).collect();
+		Ok(Page { entities, next })
+	}
+}
+
+#[async_trait::async_trait]
+impl Sortable for PgEmployee
+{
+	type SortKey = EmployeeSortKey;
+
+	/// Retrieve all [`Employee`]s (via `connection`) that match the `match_condition`, ordered by
+	/// the `sort`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Employee", rows))
+	)]
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		sort::push_order_by(&mut query, sort, sort_column, COLUMNS.default_scope().id);
+
+		let entities: Vec<_> = query
 			.prepare()
 			.fetch(connection)
 			.map_ok(|row| Self::row_to_view(COLUMNS, &row))
 			.try_collect()
-			.await
+			.await?;
+
+		trace::rows(entities.len());
+		Ok(entities)
+	}
+}
+
+#[async_trait::async_trait]
+impl Countable for PgEmployee
+{
+	/// Count the [`Employee`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Employee")))]
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>
+	{
+		let mut query = QueryBuilder::new(sql::SELECT);
+
+		query.push(count::COUNT);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+
+	/// Check whether there are any [`Employee`]s (via `connection`) that match the
+	/// `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Employee")))]
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>
+	{
+		let mut query = QueryBuilder::new(sql::SELECT);
+
+		query.push(count::EXISTS);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+		query.push(')');
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+}
+
+#[async_trait::async_trait]
+impl Explainable for PgEmployee
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the query which retrieves the
+	/// [`Employee`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Employee")))]
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::explain(connection, &mut query).await
+	}
+
+	/// Render the query which retrieves the [`Employee`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Employee")))]
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::render(connection, &mut query).await
 	}
 }
 
@@ -58,8 +250,19 @@ mod tests
 
 	use clinvoice_adapter::{schema::EmployeeAdapter, Retrievable};
 	use clinvoice_match::{Match, MatchEmployee, MatchStr};
+	use pretty_assertions::assert_eq;
 
-	use crate::schema::{util, PgEmployee};
+	use crate::schema::{
+		util,
+		Countable,
+		EmployeeSortKey,
+		Page,
+		Paginatable,
+		Pagination,
+		PgEmployee,
+		Sort,
+		Sortable,
+	};
 
 	#[tokio::test]
 	async fn retrieve()
@@ -99,7 +302,139 @@ mod tests
 			.unwrap()
 			.into_iter()
 			.collect::<HashSet<_>>(),
-			[employee, employee2].into_iter().collect()
+			[employee.clone(), employee2.clone()].into_iter().collect()
+		);
+
+		let match_condition = MatchEmployee {
+			id: Match::Or(vec![employee.id.into(), employee2.id.into()]),
+			..Default::default()
+		};
+
+		assert_eq!(PgEmployee::count(&connection, match_condition.clone()).await.unwrap(), 2);
+		assert!(PgEmployee::exists(&connection, match_condition).await.unwrap());
+
+		let match_condition = MatchEmployee {
+			id: employee.id.into(),
+			name: employee2.name.into(),
+			..Default::default()
+		};
+
+		assert_eq!(PgEmployee::count(&connection, match_condition.clone()).await.unwrap(), 0);
+		assert!(!PgEmployee::exists(&connection, match_condition).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn retrieve_page()
+	{
+		let connection = util::connect().await;
+
+		let (employee, employee2, employee3) = futures::try_join!(
+			PgEmployee::create(&connection, "A".into(), "Employed".into(), "Janitor".into()),
+			PgEmployee::create(&connection, "B".into(), "Employed".into(), "Janitor".into()),
+			PgEmployee::create(&connection, "C".into(), "Employed".into(), "Janitor".into()),
+		)
+		.unwrap();
+
+		let mut employees = [employee, employee2, employee3];
+		employees.sort_by_key(|e| e.id);
+
+		let match_condition = MatchEmployee {
+			id: Match::Or(employees.iter().map(|e| e.id.into()).collect()),
+			..Default::default()
+		};
+
+		let page =
+			PgEmployee::retrieve_page(&connection, match_condition.clone(), Pagination::Id {
+				after: None,
+				limit: 2,
+			})
+			.await
+			.unwrap();
+
+		assert_eq!(page, Page {
+			entities: employees[..2].to_vec(),
+			next:     Some(Pagination::Id { after: Some(employees[1].id), limit: 2 }),
+		});
+
+		assert_eq!(
+			PgEmployee::retrieve_page(&connection, match_condition.clone(), page.next.unwrap())
+				.await
+				.unwrap(),
+			Page { entities: employees[2..].to_vec(), next: None },
+		);
+
+		assert_eq!(
+			PgEmployee::retrieve_page(&connection, match_condition.clone(), Pagination::Offset {
+				limit:  1,
+				offset: 1,
+			})
+			.await
+			.unwrap(),
+			Page {
+				entities: employees[1..2].to_vec(),
+				next:     Some(Pagination::Offset { limit: 1, offset: 2 }),
+			},
+		);
+
+		// NOTE: the page after an empty page would be the same, so there is none
+		assert_eq!(
+			PgEmployee::retrieve_page(&connection, match_condition.clone(), Pagination::Id {
+				after: None,
+				limit: 0,
+			})
+			.await
+			.unwrap(),
+			Page { entities: Vec::new(), next: None },
+		);
+
+		assert!(PgEmployee::retrieve_page(&connection, match_condition, Pagination::TimeBegin {
+			after: None,
+			limit: 1,
+		})
+		.await
+		.is_err());
+	}
+
+	#[tokio::test]
+	async fn retrieve_sorted()
+	{
+		let connection = util::connect().await;
+
+		let (employee, employee2, employee3, employee4) = futures::try_join!(
+			PgEmployee::create(&connection, "B".into(), "Employed".into(), "Janitor".into()),
+			PgEmployee::create(&connection, "A".into(), "Employed".into(), "Janitor".into()),
+			PgEmployee::create(&connection, "C".into(), "Fired".into(), "Janitor".into()),
+			PgEmployee::create(&connection, "A".into(), "Employed".into(), "Janitor".into()),
+		)
+		.unwrap();
+
+		// NOTE: employees with the same status and name are ordered by their id
+		let (first_a, second_a) = if employee2.id < employee4.id
+		{
+			(employee2.clone(), employee4.clone())
+		}
+		else
+		{
+			(employee4.clone(), employee2.clone())
+		};
+
+		assert_eq!(
+			PgEmployee::retrieve_sorted(
+				&connection,
+				MatchEmployee {
+					id: Match::Or(vec![
+						employee.id.into(),
+						employee2.id.into(),
+						employee3.id.into(),
+						employee4.id.into(),
+					]),
+					..Default::default()
+				},
+				&[Sort::desc(EmployeeSortKey::Status), Sort::asc(EmployeeSortKey::Name)],
+			)
+			.await
+			.unwrap(),
+			[employee3, first_a, second_a, employee],
 		);
 	}
 }
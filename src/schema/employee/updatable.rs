@@ -11,6 +11,10 @@ impl Updatable for PgEmployee
 	type Db = Postgres;
 	type Entity = Employee;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Employee", rows))
+	)]
 	async fn update<'entity, Iter>(
 		connection: &mut Transaction<Self::Db>,
 		entities: Iter,
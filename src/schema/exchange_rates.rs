@@ -0,0 +1,325 @@
+//! # Summary
+//!
+//! This module contains the [`ExchangeRatesSource`] trait, which tells [`PgSchema`] where to get
+//! [`ExchangeRates`] from, along with several implementors of it.
+
+use core::{fmt::Write, time::Duration};
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	io,
+	time::Instant,
+};
+
+use clinvoice_schema::chrono::NaiveDate;
+use money2::{Currency, Decimal, ExchangeRates};
+use sqlx::{Error, Executor, Pool, Postgres, Result};
+
+use super::{trace, util};
+#[cfg(doc)]
+use crate::PgSchema;
+
+/// A source of [`ExchangeRates`] for [`PgSchema`] to use whenever [`Money`](money2::Money) must be
+/// exchanged.
+///
+/// # See also
+///
+/// * [`PgSchema::exchange`], which takes the source to use as an argument.
+#[async_trait::async_trait]
+pub trait ExchangeRatesSource: Send + Sync
+{
+	/// Retrieve the [`ExchangeRates`] which were effective on the given `date`, using `connection`
+	/// if necessary.
+	async fn exchange_rates(
+		&self,
+		connection: &Pool<Postgres>,
+		date: NaiveDate,
+	) -> Result<ExchangeRates>;
+}
+
+/// Retrieves the latest [`ExchangeRates`] from the European Central Bank over the network.
+///
+/// It cannot retrieve historical rates, so the `date` is ignored. Prefer [`PgExchangeRates`] where
+/// network access is not desired.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct EcbExchangeRates;
+
+#[async_trait::async_trait]
+impl ExchangeRatesSource for EcbExchangeRates
+{
+	async fn exchange_rates(&self, _: &Pool<Postgres>, _: NaiveDate) -> Result<ExchangeRates>
+	{
+		ExchangeRates::new().await.map_err(util::finance_err_to_sqlx)
+	}
+}
+
+/// Reads [`ExchangeRates`] from the `exchange_rates` table, so that no network access is required.
+///
+/// The `rate` of each `currency` is how much of that currency is worth one
+/// [`Currency::Eur`]. The rate of [`Currency::Eur`] is always `1`, and need not be stored. The rate
+/// which is effective on a given date is the most recent one on or before that date.
+///
+/// # See also
+///
+/// * [`PgSchema::import_exchange_rates`] and [`PgSchema::import_ecb_exchange_rates`] to fill the
+///   table.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PgExchangeRates;
+
+#[async_trait::async_trait]
+impl ExchangeRatesSource for PgExchangeRates
+{
+	async fn exchange_rates(
+		&self,
+		connection: &Pool<Postgres>,
+		date: NaiveDate,
+	) -> Result<ExchangeRates>
+	{
+		let rows: Vec<(String, Decimal)> = sqlx::query_as(
+			"SELECT DISTINCT ON (currency) currency::text, rate
+			FROM exchange_rates
+			WHERE date <= $1
+			ORDER BY currency, date DESC;",
+		)
+		.bind(date)
+		.fetch_all(connection)
+		.await?;
+
+		let rates = rows
+			.into_iter()
+			.map(|(currency, rate)| {
+				currency.parse::<Currency>().map(|c| (c, rate)).map_err(util::finance_err_to_sqlx)
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		exchange_rates_from(date, rates)
+	}
+}
+
+/// Always provides the same [`ExchangeRates`], which are kept in memory. Useful for tests.
+///
+/// The `date` is ignored.
+#[derive(Clone, Debug)]
+pub struct StaticExchangeRates(pub ExchangeRates);
+
+#[async_trait::async_trait]
+impl ExchangeRatesSource for StaticExchangeRates
+{
+	async fn exchange_rates(&self, _: &Pool<Postgres>, _: NaiveDate) -> Result<ExchangeRates>
+	{
+		Ok(self.0.clone())
+	}
+}
+
+/// [`ExchangeRates`] which have already been retrieved from an [`ExchangeRatesSource`], keyed by
+/// the date they were effective on.
+///
+/// Use this when exchanging many values, so that the rates of each date are only retrieved once.
+pub(super) struct RatesByDate<'connection, 'source>
+{
+	/// The connection to retrieve the [`ExchangeRates`] with.
+	connection: &'connection Pool<Postgres>,
+
+	/// The total time spent retrieving [`ExchangeRates`] so far.
+	elapsed: Duration,
+
+	/// The [`ExchangeRates`] which have been retrieved so far.
+	rates: HashMap<NaiveDate, ExchangeRates>,
+
+	/// Where to retrieve the [`ExchangeRates`] from.
+	source: &'source dyn ExchangeRatesSource,
+}
+
+impl<'connection, 'source> RatesByDate<'connection, 'source>
+{
+	/// Create a new, empty [`RatesByDate`] which retrieves [`ExchangeRates`] from the `source`.
+	pub(super) fn new(
+		connection: &'connection Pool<Postgres>,
+		source: &'source dyn ExchangeRatesSource,
+	) -> Self
+	{
+		Self { connection, elapsed: Duration::ZERO, rates: HashMap::new(), source }
+	}
+
+	/// Get the [`ExchangeRates`] which were effective on the given `date`.
+	pub(super) async fn on(&mut self, date: NaiveDate) -> Result<&ExchangeRates>
+	{
+		Ok(match self.rates.entry(date)
+		{
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) =>
+			{
+				let start = Instant::now();
+				let rates = self.source.exchange_rates(self.connection, date).await;
+
+				self.elapsed += start.elapsed();
+				trace::exchange_rates(self.elapsed);
+
+				entry.insert(rates?)
+			},
+		})
+	}
+}
+
+/// Construct the [`ExchangeRates`] effective on `date` from the `rate` of each [`Currency`]
+/// relative to [`Currency::Eur`].
+///
+/// [`ExchangeRates`] can only be constructed from the CSV format published by the European
+/// Central Bank, so the `rates` are written in that format and then parsed.
+pub(super) fn exchange_rates_from<Iter>(date: NaiveDate, rates: Iter) -> Result<ExchangeRates>
+where
+	Iter: IntoIterator<Item = (Currency, Decimal)>,
+{
+	let mut currencies = String::from("Date, ");
+	let mut values = date.format("%d %B %Y, ").to_string();
+
+	rates.into_iter().filter(|(c, _)| *c != Currency::Eur).for_each(|(currency, rate)| {
+		// NOTE: `write!`ing to a `String` cannot fail
+		write!(currencies, "{currency}, ").ok();
+		write!(values, "{rate}, ").ok();
+	});
+
+	format!("{currencies}\n{values}\n").parse().map_err(util::finance_err_to_sqlx)
+}
+
+/// Insert the `rate` of each [`Currency`] on each date into the `exchange_rates` table, replacing
+/// any rate which was already there for the same [`Currency`] and date.
+pub(super) async fn import<'connection, Conn, Iter>(connection: Conn, rates: Iter) -> Result<()>
+where
+	Conn: Executor<'connection, Database = Postgres>,
+	Iter: IntoIterator<Item = (NaiveDate, Currency, Decimal)>,
+{
+	let (mut dates, mut currencies, mut values) = (Vec::new(), Vec::new(), Vec::new());
+	rates.into_iter().for_each(|(date, currency, rate)| {
+		dates.push(date);
+		currencies.push(currency.to_string());
+		values.push(rate);
+	});
+
+	sqlx::query(
+		"INSERT INTO exchange_rates (currency, date, rate)
+		SELECT * FROM UNNEST($1::text[], $2::date[], $3::numeric[])
+		ON CONFLICT (currency, date) DO UPDATE SET rate = EXCLUDED.rate;",
+	)
+	.bind(currencies)
+	.bind(dates)
+	.bind(values)
+	.execute(connection)
+	.await?;
+
+	Ok(())
+}
+
+/// Parse the historical rates published by the European Central Bank (i.e. `eurofxref-hist.csv`)
+/// into the date, [`Currency`], and rate of each entry.
+///
+/// Rates which are `N/A`, or for a [`Currency`] which is not supported, are skipped.
+pub(super) fn parse_ecb_history(csv: &str) -> Result<Vec<(NaiveDate, Currency, Decimal)>>
+{
+	let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+	let currencies: Vec<Option<Currency>> = lines
+		.next()
+		.map(|header| header.split(',').skip(1).map(|c| c.trim().parse().ok()).collect())
+		.unwrap_or_default();
+
+	let mut rates = Vec::new();
+	for line in lines
+	{
+		let mut fields = line.split(',').map(str::trim);
+		let date = fields
+			.next()
+			.and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+			.ok_or_else(|| {
+				Error::Io(io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("Expected a date at the start of the exchange rates `{line}`"),
+				))
+			})?;
+
+		for (currency, rate) in currencies.iter().zip(fields)
+		{
+			if let (Some(c), Ok(r)) = (currency, rate.parse::<Decimal>())
+			{
+				rates.push((date, *c, r));
+			}
+		}
+	}
+
+	Ok(rates)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use clinvoice_schema::chrono::NaiveDate;
+	use money2::{Currency, Decimal, Exchange, Money};
+	use pretty_assertions::assert_eq;
+
+	use super::{ExchangeRatesSource, PgExchangeRates, StaticExchangeRates};
+	use crate::{schema::util, PgSchema};
+
+	#[tokio::test]
+	async fn exchange_rates()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		let first = NaiveDate::from_ymd_opt(1999, 1, 4).unwrap();
+		let second = NaiveDate::from_ymd_opt(1999, 1, 5).unwrap();
+		let rates = super::exchange_rates_from(first, [
+			(Currency::Eur, Decimal::new(1, 0)),
+			(Currency::Jpy, Decimal::new(150, 0)),
+			(Currency::Usd, Decimal::new(2, 0)),
+		])
+		.unwrap();
+
+		let usd = Money::new(3_00, 2, Currency::Usd);
+		let static_rates =
+			StaticExchangeRates(rates.clone()).exchange_rates(&connection, second).await.unwrap();
+
+		assert_eq!(usd.exchange(Currency::Jpy, &static_rates), usd.exchange(Currency::Jpy, &rates));
+		assert_eq!(usd.exchange(Currency::Eur, &static_rates).amount, Decimal::new(1_50, 2));
+
+		// NOTE: other tests only import rates in later months of 1999, so that these are the only
+		//       rates effective in January.
+		PgSchema::import_ecb_exchange_rates(
+			&connection,
+			"Date,USD,JPY,N/A,\n1999-01-05,4,N/A,,\n1999-01-04,2,150,,\n",
+		)
+		.await
+		.unwrap();
+
+		let pg_rates = PgExchangeRates.exchange_rates(&connection, first).await.unwrap();
+		assert_eq!(usd.exchange(Currency::Jpy, &pg_rates), usd.exchange(Currency::Jpy, &rates));
+
+		// The USD rate of the second date replaces the first, but the JPY rate is still effective
+		let pg_rates = PgExchangeRates.exchange_rates(&connection, second).await.unwrap();
+		assert_eq!(usd.exchange(Currency::Eur, &pg_rates).amount, Decimal::new(75, 2));
+		assert_eq!(
+			usd.exchange(Currency::Jpy, &pg_rates),
+			usd.exchange(
+				Currency::Jpy,
+				&super::exchange_rates_from(second, [
+					(Currency::Jpy, Decimal::new(150, 0)),
+					(Currency::Usd, Decimal::new(4, 0)),
+				])
+				.unwrap(),
+			),
+		);
+	}
+
+	#[test]
+	fn parse_ecb_history()
+	{
+		assert_eq!(
+			super::parse_ecb_history("Date, USD, XYZ, JPY, \n2022-06-17, 1.0515, 1, N/A, \n")
+				.unwrap(),
+			[(
+				NaiveDate::from_ymd_opt(2022, 6, 17).unwrap(),
+				Currency::Usd,
+				Decimal::new(1_0515, 4),
+			)],
+		);
+
+		assert!(super::parse_ecb_history("Date,USD\nyesterday,1.05\n").is_err());
+	}
+}
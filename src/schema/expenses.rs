@@ -1,14 +1,27 @@
 mod deletable;
 mod expenses_adapter;
 mod retrievable;
+mod totals;
 mod updatable;
 
-use clinvoice_adapter::schema::columns::ExpenseColumns;
-use clinvoice_schema::Expense;
-use money2::{Decimal, Money};
-use sqlx::{postgres::PgRow, Result, Row};
+use std::collections::HashMap;
 
-use super::util;
+use clinvoice_adapter::{fmt::sql, schema::columns::ExpenseColumns};
+use clinvoice_match::{MatchExpense, MatchTimesheet};
+use clinvoice_schema::{
+	chrono::{DateTime, Utc},
+	Expense,
+	Id,
+};
+use money2::{Currency, Exchange};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
+pub use totals::ExpenseTotal;
+
+use super::{exchange_rates::RatesByDate, util, ExchangeRatesSource, Period, StrComparison};
+
+/// The column of the `expenses` table which stores the [`Currency`](money2::Currency) of the
+/// `cost`.
+pub(super) const COST_CURRENCY: &str = "cost_currency";
 
 /// Implementor of the [`ExpensesAdapter`](clinvoice_adapter::schema::ExpensesAdapter) for the
 /// [`Postgres`](sqlx::Postgres) database.
@@ -16,19 +29,98 @@ pub struct PgExpenses;
 
 impl PgExpenses
 {
+	/// Exchange the [`Expense::cost`] of each of the `expenses` into the given `currency`.
+	///
+	/// The exchange rates used are those effective on the
+	/// [`Timesheet::time_begin`](clinvoice_schema::Timesheet::time_begin) of the timesheet which
+	/// the [`Expense`] belongs to. They are retrieved from the `source`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Expense", exchange_rates_us))
+	)]
+	pub async fn exchange<Iter>(
+		connection: &Pool<Postgres>,
+		source: &dyn ExchangeRatesSource,
+		expenses: Iter,
+		currency: Currency,
+	) -> Result<Vec<Expense>>
+	where
+		Iter: IntoIterator<Item = Expense>,
+	{
+		let expenses: Vec<_> = expenses.into_iter().collect();
+		let timesheet_ids: Vec<_> = expenses.iter().map(|x| x.timesheet_id).collect();
+
+		let time_begins: HashMap<Id, DateTime<Utc>> =
+			sqlx::query_as("SELECT id, time_begin FROM timesheets WHERE id = ANY($1);")
+				.bind(timesheet_ids)
+				.fetch_all(connection)
+				.await?
+				.into_iter()
+				.collect();
+
+		let mut rates = RatesByDate::new(connection, source);
+		let mut exchanged = Vec::with_capacity(expenses.len());
+
+		for expense in expenses
+		{
+			// NOTE: an `Expense` cannot exist without its timesheet, but it may have been deleted
+			//       since the `Expense` was retrieved.
+			let date = time_begins.get(&expense.timesheet_id).map_or_else(Utc::now, |t| *t);
+			exchanged.push(expense.exchange(currency, rates.on(date.naive_utc().date()).await?));
+		}
+
+		Ok(exchanged)
+	}
+
+	/// Write `(SELECT … FROM … WHERE …)`, which selects the [`Id`] of every [`Expense`] that
+	/// matches the `match_condition`, to the `query`.
+	pub(super) fn push_matching_ids(
+		query: &mut QueryBuilder<Postgres>,
+		match_condition: &MatchExpense,
+	)
+	{
+		query.push('(').push(sql::SELECT).push(ExpenseColumns::default().default_scope().id);
+		retrievable::push_from_where(query, match_condition, StrComparison::Exact);
+		query.push(')');
+	}
+
 	pub(super) fn row_to_view(columns: ExpenseColumns<&str>, row: &PgRow) -> Result<Expense>
 	{
 		Ok(Expense {
 			id: row.try_get(columns.id)?,
 			timesheet_id: row.try_get(columns.timesheet_id)?,
 			category: row.try_get(columns.category)?,
-			cost: Money {
-				amount: row.try_get::<String, _>(columns.cost).and_then(|cost| {
-					cost.parse::<Decimal>().map_err(|e| util::finance_err_to_sqlx(e.into()))
-				})?,
-				..Default::default()
-			},
+			cost: util::money_from(row.try_get(columns.cost)?, row.try_get(COST_CURRENCY)?)?,
 			description: row.try_get(columns.description)?,
 		})
 	}
+
+	/// Retrieve the [`ExpenseTotal`]s of the [`Expense`]s that match the `match_condition`, and
+	/// whose [`Timesheet`](clinvoice_schema::Timesheet) matches the `timesheet_match_condition`:
+	/// the total cost in each category, for each [`Job`](clinvoice_schema::Job), in each
+	/// `period`.
+	///
+	/// Each [`Expense::cost`] is exchanged into the `currency` by the database, using the rates in
+	/// the `exchange_rates` table (see [`PgExchangeRates`](super::PgExchangeRates)) effective on
+	/// the [`Timesheet::time_begin`](clinvoice_schema::Timesheet::time_begin) of its timesheet.
+	///
+	/// # Errors
+	///
+	/// * [`Error::ColumnDecode`](sqlx::Error::ColumnDecode) if an [`Expense::cost`] could not be
+	///   exchanged, because the `exchange_rates` table has no rate for its currency on that date.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Expense", rows))
+	)]
+	pub async fn totals(
+		connection: &Pool<Postgres>,
+		match_condition: MatchExpense,
+		timesheet_match_condition: MatchTimesheet,
+		period: Period,
+		currency: Currency,
+	) -> Result<Vec<ExpenseTotal>>
+	{
+		totals::retrieve(connection, &match_condition, &timesheet_match_condition, period, currency)
+			.await
+	}
 }
@@ -11,6 +11,10 @@ impl Deletable for PgExpenses
 	type Db = Postgres;
 	type Entity = Expense;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Expense", rows))
+	)]
 	async fn delete<'connection, 'entity, Conn, Iter>(
 		connection: Conn,
 		entities: Iter,
@@ -51,7 +55,7 @@ mod tests
 		chrono::{TimeZone, Utc},
 		Invoice,
 	};
-	use money2::{Currency, Exchange, ExchangeRates, Money};
+	use money2::{Currency, Money};
 	use pretty_assertions::assert_eq;
 
 	use crate::schema::{
@@ -125,8 +129,6 @@ mod tests
 		.await
 		.unwrap();
 
-		let exchange_rates = ExchangeRates::new().await.unwrap();
-
 		assert_eq!(
 			PgExpenses::retrieve(&connection, MatchExpense {
 				timesheet_id: timesheet.id.into(),
@@ -138,7 +140,7 @@ mod tests
 			.filter(|x| x.timesheet_id == timesheet.id)
 			.collect::<Vec<_>>()
 			.as_slice(),
-			&[timesheet.expenses[2].clone().exchange(Default::default(), &exchange_rates)],
+			&[timesheet.expenses[2].clone()],
 		);
 	}
 }
@@ -3,16 +3,16 @@ use clinvoice_adapter::{
 	schema::{columns::ExpenseColumns, ExpensesAdapter},
 };
 use clinvoice_schema::{Expense, Id};
-use futures::{stream, StreamExt, TryFutureExt, TryStreamExt};
-use money2::{Exchange, ExchangeRates, Money};
+use futures::{stream, StreamExt, TryStreamExt};
+use money2::Money;
 use sqlx::{Executor, Postgres, QueryBuilder, Result, Row};
 
 use super::PgExpenses;
-use crate::schema::util;
 
 #[async_trait::async_trait]
 impl ExpensesAdapter for PgExpenses
 {
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Expense")))]
 	async fn create<'connection, Conn>(
 		connection: Conn,
 		expenses: Vec<(String, Money, String)>,
@@ -28,16 +28,15 @@ impl ExpensesAdapter for PgExpenses
 			return Ok(Vec::new());
 		}
 
-		let exchange_rates = ExchangeRates::new().map_err(util::finance_err_to_sqlx).await?;
-
 		QueryBuilder::new(
 			"INSERT INTO expenses
-				(timesheet_id, category, cost, description) ",
+				(timesheet_id, category, cost, cost_currency, description) ",
 		)
 		.push_values(expenses.iter(), |mut q, (category, cost, description)| {
 			q.push_bind(timesheet_id)
 				.push_bind(category)
-				.push_bind(cost.exchange(Default::default(), &exchange_rates).amount.to_string())
+				.push_bind(cost.amount)
+				.push_bind(cost.currency.to_string())
 				.push_bind(description);
 		})
 		.push(sql::RETURNING)
@@ -2,16 +2,85 @@ use clinvoice_adapter::{
 	fmt::{sql, QueryBuilderExt, TableToSql},
 	schema::columns::ExpenseColumns,
 	Retrievable,
-	WriteWhereClause,
+	WriteContext,
 };
 use clinvoice_match::MatchExpense;
 use clinvoice_schema::Expense;
-use futures::{future, TryFutureExt, TryStreamExt};
-use money2::{Exchange, ExchangeRates};
-use sqlx::{Pool, Postgres, QueryBuilder, Result};
+use futures::{future, TryStreamExt};
+use sqlx::{Pool, Postgres, QueryBuilder, Result, Row};
 
-use super::PgExpenses;
-use crate::{schema::util, PgSchema};
+use super::{PgExpenses, COST_CURRENCY};
+use crate::schema::{
+	count,
+	explain,
+	sort,
+	trace,
+	write_where_clause::WriteWhere,
+	Countable,
+	ExpenseSortKey,
+	Explainable,
+	Page,
+	Paginatable,
+	Pagination,
+	RenderedQuery,
+	Sort,
+	Sortable,
+	StrComparable,
+	StrComparison,
+};
+
+const COLUMNS: ExpenseColumns<&str> = ExpenseColumns::default();
+
+/// Generate the query which retrieves all [`Expense`]s that match the `match_condition`, and the
+/// [`WriteContext`] which follows its `WHERE` clause.
+fn query(
+	match_condition: &MatchExpense,
+	comparison: StrComparison,
+) -> (QueryBuilder<Postgres>, WriteContext)
+{
+	let mut query = QueryBuilder::new(sql::SELECT);
+
+	query
+		.push_columns(&COLUMNS.default_scope())
+		.push(',')
+		.push(ExpenseColumns::<char>::DEFAULT_ALIAS)
+		.push('.')
+		.push(COST_CURRENCY);
+
+	let context = push_from_where(&mut query, match_condition, comparison);
+
+	(query, context)
+}
+
+/// Write the `FROM` and `WHERE` clauses which select the [`Expense`]s that match the
+/// `match_condition` to the `query`, and return the [`WriteContext`] which follows them.
+pub(super) fn push_from_where(
+	query: &mut QueryBuilder<Postgres>,
+	match_condition: &MatchExpense,
+	comparison: StrComparison,
+) -> WriteContext
+{
+	query.push_default_from::<ExpenseColumns<char>>();
+
+	comparison.write_where(
+		Default::default(),
+		ExpenseColumns::<char>::DEFAULT_ALIAS,
+		match_condition,
+		query,
+	)
+}
+
+/// The (scoped) column which [`Expense`]s are sorted by for the `key`.
+fn sort_column(key: ExpenseSortKey) -> String
+{
+	let columns = COLUMNS.default_scope();
+	match key
+	{
+		ExpenseSortKey::Category => columns.category.to_string(),
+		ExpenseSortKey::Id => columns.id.to_string(),
+		ExpenseSortKey::TimesheetId => columns.timesheet_id.to_string(),
+	}
+}
 
 /// Implementors of this trait are capable of being retrieved from a [`Database`].
 #[async_trait::async_trait]
@@ -30,30 +99,152 @@ impl Retrievable for PgExpenses
 		match_condition: Self::Match,
 	) -> Result<Vec<Self::Entity>>
 	{
-		const COLUMNS: ExpenseColumns<&str> = ExpenseColumns::default();
+		Self::retrieve_comparing(connection, match_condition, StrComparison::Exact).await
+	}
+}
 
+#[async_trait::async_trait]
+impl StrComparable for PgExpenses
+{
+	/// Retrieve all [`Expense`]s (via `connection`) that match the `match_condition`, comparing
+	/// strings according to the `comparison`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Expense", rows))
+	)]
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, comparison);
+
+		let entities: Vec<_> = query
+			.prepare()
+			.fetch(connection)
+			.and_then(|row| future::ready(Self::row_to_view(COLUMNS, &row)))
+			.try_collect()
+			.await?;
+
+		trace::rows(entities.len());
+		Ok(entities)
+	}
+}
+
+#[async_trait::async_trait]
+impl Paginatable for PgExpenses
+{
+	/// Retrieve the [`Page`] of [`Expense`]s (via `connection`) that match the `match_condition`
+	/// which is described by the `pagination`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Expense", rows))
+	)]
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>
+	{
 		let columns = COLUMNS.default_scope();
-		let exchange_rates_fut = ExchangeRates::new().map_err(util::finance_err_to_sqlx);
-		let mut query = QueryBuilder::new(sql::SELECT);
+		let (mut query, context) = query(&match_condition, StrComparison::Exact);
 
-		query.push_columns(&columns).push_default_from::<ExpenseColumns<char>>();
+		pagination.push_where(&mut query, context, columns.id, None)?;
+		pagination.push_order_limit(&mut query, columns.id, None)?;
 
-		let exchanged_condition = exchange_rates_fut
-			.await
-			.map(|rates| match_condition.exchange(Default::default(), &rates))?;
+		let mut rows = query.prepare().fetch_all(connection).await?;
+		let next = pagination.next(&mut rows, COLUMNS.id, None)?;
+		trace::rows(rows.len());
 
-		PgSchema::write_where_clause(
-			Default::default(),
-			ExpenseColumns::<char>::DEFAULT_ALIAS,
-			&exchanged_condition,
-			&mut query,
-		);
+		let entities =
+			rows.iter().map(|row| Self::row_to_view(COLUMNS, row)).collect::<Result<_>>()?;
+		Ok(Page { entities, next })
+	}
+}
+
+#[async_trait::async_trait]
+impl Sortable for PgExpenses
+{
+	type SortKey = ExpenseSortKey;
 
-		query
+	/// Retrieve all [`Expense`]s (via `connection`) that match the `match_condition`, ordered by
+	/// the `sort`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Expense", rows))
+	)]
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		sort::push_order_by(&mut query, sort, sort_column, COLUMNS.default_scope().id);
+
+		let entities: Vec<_> = query
 			.prepare()
 			.fetch(connection)
 			.and_then(|row| future::ready(Self::row_to_view(COLUMNS, &row)))
 			.try_collect()
-			.await
+			.await?;
+
+		trace::rows(entities.len());
+		Ok(entities)
+	}
+}
+
+#[async_trait::async_trait]
+impl Countable for PgExpenses
+{
+	/// Count the [`Expense`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Expense")))]
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>
+	{
+		let mut query = QueryBuilder::new(sql::SELECT);
+
+		query.push(count::COUNT);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+
+	/// Check whether there are any [`Expense`]s (via `connection`) that match the
+	/// `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Expense")))]
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>
+	{
+		let mut query = QueryBuilder::new(sql::SELECT);
+
+		query.push(count::EXISTS);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+		query.push(')');
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+}
+
+#[async_trait::async_trait]
+impl Explainable for PgExpenses
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the query which retrieves the
+	/// [`Expense`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Expense")))]
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::explain(connection, &mut query).await
+	}
+
+	/// Render the query which retrieves the [`Expense`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Expense")))]
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::render(connection, &mut query).await
 	}
 }
@@ -0,0 +1,248 @@
+use clinvoice_adapter::fmt::QueryBuilderExt;
+use clinvoice_match::{MatchExpense, MatchTimesheet};
+use clinvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+use money2::{Currency, Money};
+use sqlx::{postgres::PgRow, Pool, Postgres, Result, Row};
+
+use super::PgExpenses;
+use crate::schema::{trace, util, Period, PgLocation, PgTimesheet, StrComparison};
+
+/// The total cost of the [`Expense`](clinvoice_schema::Expense)s in a single category, which were
+/// incurred for a single [`Job`](clinvoice_schema::Job) in a single [`Period`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ExpenseTotal
+{
+	/// The [`Expense::category`](clinvoice_schema::Expense::category).
+	pub category: String,
+
+	/// The total [`Expense::cost`](clinvoice_schema::Expense::cost).
+	pub cost: Money,
+
+	/// The number of [`Expense`](clinvoice_schema::Expense)s.
+	pub expenses: i64,
+
+	/// The [`Id`] of the [`Job`](clinvoice_schema::Job).
+	pub job_id: Id,
+
+	/// The [`Id`] of the client [`Organization`](clinvoice_schema::Organization) of the
+	/// [`Job`](clinvoice_schema::Job).
+	pub organization_id: Id,
+
+	/// When the [`Period`] began.
+	pub period: DateTime<Utc>,
+}
+
+impl ExpenseTotal
+{
+	/// Construct an [`ExpenseTotal`] from the `row`, whose `cost` is in the `currency`.
+	fn from_row(row: &PgRow, currency: &str) -> Result<Self>
+	{
+		Ok(Self {
+			category: row.try_get("category")?,
+			cost: util::money_from(row.try_get("cost")?, currency)?,
+			expenses: row.try_get("expenses")?,
+			job_id: row.try_get("job_id")?,
+			organization_id: row.try_get("organization_id")?,
+			period: row.try_get("period")?,
+		})
+	}
+}
+
+/// Retrieve the [`ExpenseTotal`]s of the [`Expense`](clinvoice_schema::Expense)s which match the
+/// `match_condition`, and whose [`Timesheet`](clinvoice_schema::Timesheet) matches the
+/// `timesheet_match_condition`.
+///
+/// The totals are sorted by [`ExpenseTotal::period`], then
+/// [`ExpenseTotal::organization_id`], then [`ExpenseTotal::job_id`], then
+/// [`ExpenseTotal::category`]. An [`Expense`](clinvoice_schema::Expense) belongs to the `period`
+/// in which its [`Timesheet`](clinvoice_schema::Timesheet) began.
+pub(super) async fn retrieve(
+	connection: &Pool<Postgres>,
+	match_condition: &MatchExpense,
+	timesheet_match_condition: &MatchTimesheet,
+	period: Period,
+	currency: Currency,
+) -> Result<Vec<ExpenseTotal>>
+{
+	let currency = currency.to_string();
+	let mut query = PgLocation::query_with_recursive(
+		&timesheet_match_condition.job.client.location,
+		StrComparison::Exact,
+	);
+
+	// NOTE: if any cost could not be exchanged, the total is `NULL` so that decoding it fails
+	//       instead of silently leaving the cost out of the sum.
+	query.push(
+		" SELECT P.period, J.client_id AS organization_id, T.job_id, X.category,
+			count(*) AS expenses,
+			CASE WHEN count(*) = count(C.cost) THEN sum(C.cost) END AS cost
+		FROM expenses X
+		JOIN timesheets T ON (T.id = X.timesheet_id)
+		JOIN jobs J ON (J.id = T.job_id)
+		CROSS JOIN LATERAL (SELECT ",
+	);
+
+	period.push_date_trunc(&mut query, "T.time_begin");
+
+	query
+		.push(
+			" AS period) P
+			CROSS JOIN LATERAL
+			(
+				SELECT exchange(X.cost, X.cost_currency, ",
+		)
+		.push_bind(currency.clone())
+		.push(
+			"::currency_code, (T.time_begin AT TIME ZONE 'UTC')::date) AS cost
+			) C
+			WHERE X.id IN ",
+		);
+
+	PgExpenses::push_matching_ids(&mut query, match_condition);
+	query.push(" AND T.id IN ");
+	PgTimesheet::push_matching_ids(&mut query, timesheet_match_condition);
+
+	query.push(
+		" GROUP BY P.period, J.client_id, T.job_id, X.category
+		ORDER BY P.period, J.client_id, T.job_id, X.category;",
+	);
+
+	let rows = query.prepare().fetch_all(connection).await?;
+	trace::rows(rows.len());
+	rows.iter().map(|row| ExpenseTotal::from_row(row, &currency)).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	};
+	use clinvoice_match::{MatchExpense, MatchJob, MatchStr, MatchTimesheet};
+	use clinvoice_schema::{
+		chrono::{self, NaiveDate, TimeZone, Utc},
+		Invoice,
+	};
+	use money2::{Currency, Decimal, Money};
+	use pretty_assertions::assert_eq;
+
+	use super::ExpenseTotal;
+	use crate::{
+		schema::{
+			util,
+			Period,
+			PgEmployee,
+			PgExpenses,
+			PgJob,
+			PgLocation,
+			PgOrganization,
+			PgTimesheet,
+		},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn retrieve()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		// NOTE: no other test imports exchange rates in March 1999
+		let date = NaiveDate::from_ymd_opt(1999, 3, 1).unwrap();
+		PgSchema::import_exchange_rates(&connection, [(date, Currency::Usd, Decimal::new(2, 0))])
+			.await
+			.unwrap();
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Organization".into()).await.unwrap();
+		let employee =
+			PgEmployee::create(&connection, "My Name".into(), "Employed".into(), "Janitor".into())
+				.await
+				.unwrap();
+
+		let job = PgJob::create(
+			&connection,
+			organization.clone(),
+			None,
+			Utc.ymd(1999, 03, 01).and_hms(08, 00, 00),
+			Duration::from_secs(900),
+			Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			String::new(),
+			"Travel".into(),
+		)
+		.await
+		.unwrap();
+
+		// {{{
+		let mut transaction = connection.begin().await.unwrap();
+
+		for (time_begin, expenses) in [
+			(Utc.ymd(1999, 03, 01).and_hms(09, 00, 00), vec![
+				("Food".into(), Money::new(4_50, 2, Currency::Eur), "Lunch".into()),
+				("Food".into(), Money::new(1_00, 2, Currency::Usd), "Snack".into()),
+				("Travel".into(), Money::new(10_00, 2, Currency::Usd), "Bus".into()),
+			]),
+			(Utc.ymd(1999, 03, 02).and_hms(09, 00, 00), vec![(
+				"Lodging".into(),
+				Money::new(50_00, 2, Currency::Eur),
+				"Hotel".into(),
+			)]),
+		]
+		{
+			PgTimesheet::create(
+				&mut transaction,
+				employee.clone(),
+				expenses,
+				job.clone(),
+				time_begin,
+				Some(time_begin + chrono::Duration::hours(1)),
+				String::new(),
+			)
+			.await
+			.unwrap();
+		}
+
+		transaction.commit().await.unwrap();
+		// }}}
+
+		let timesheet_match_condition = MatchTimesheet {
+			job: MatchJob { id: job.id.into(), ..Default::default() },
+			..Default::default()
+		};
+
+		let total = |category: &str, cost, expenses| ExpenseTotal {
+			category: category.into(),
+			cost: Money::new(cost, 2, Currency::Usd),
+			expenses,
+			job_id: job.id,
+			organization_id: organization.id,
+			period: Utc.ymd(1999, 03, 01).and_hms(00, 00, 00),
+		};
+
+		assert_eq!(
+			PgExpenses::totals(
+				&connection,
+				MatchExpense {
+					category: MatchStr::Not(MatchStr::from("Lodging".to_string()).into()),
+					..Default::default()
+				},
+				timesheet_match_condition,
+				Period::Month,
+				Currency::Usd,
+			)
+			.await
+			.unwrap(),
+			[total("Food", 10_00, 2), total("Travel", 10_00, 1)],
+		);
+	}
+}
@@ -2,7 +2,7 @@ use clinvoice_adapter::{schema::columns::ExpenseColumns, Updatable};
 use clinvoice_schema::Expense;
 use sqlx::{Postgres, Result, Transaction};
 
-use super::PgExpenses;
+use super::{PgExpenses, COST_CURRENCY};
 use crate::PgSchema;
 
 #[async_trait::async_trait]
@@ -11,6 +11,10 @@ impl Updatable for PgExpenses
 	type Db = Postgres;
 	type Entity = Expense;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Expense", rows))
+	)]
 	async fn update<'entity, Iter>(
 		connection: &mut Transaction<Self::Db>,
 		entities: Iter,
@@ -19,7 +23,7 @@ impl Updatable for PgExpenses
 		Self::Entity: 'entity,
 		Iter: Clone + Iterator<Item = &'entity Self::Entity> + Send,
 	{
-		let mut peekable_entities = entities.peekable();
+		let mut peekable_entities = entities.clone().peekable();
 
 		// There is nothing to do.
 		if peekable_entities.peek().is_none()
@@ -30,12 +34,19 @@ impl Updatable for PgExpenses
 		PgSchema::update(connection, ExpenseColumns::default(), |query| {
 			query.push_values(peekable_entities, |mut q, e| {
 				q.push_bind(&e.category)
-					.push_bind(e.cost.amount.to_string())
+					.push_bind(e.cost.amount)
 					.push_bind(&e.description)
 					.push_bind(e.id)
 					.push_bind(e.timesheet_id);
 			});
 		})
+		.await?;
+
+		PgSchema::update_currency::<_, ExpenseColumns<char>>(
+			connection,
+			COST_CURRENCY,
+			entities.map(|e| (e.id, e.cost.currency)),
+		)
 		.await
 	}
 }
@@ -0,0 +1,179 @@
+//! # Summary
+//!
+//! This module contains a trait which allows the query run by a [`Retrievable`] to be inspected
+//! without retrieving any entities.
+
+use clinvoice_adapter::Retrievable;
+use sqlx::{Execute, Pool, Postgres, QueryBuilder, Result, Row};
+
+/// An argument which is bound to a parameter (e.g. `$1`) of a [`RenderedQuery`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BoundArgument
+{
+	/// The name of the Postgres type of the argument (e.g. `bigint`).
+	pub r#type: String,
+
+	/// The argument, as Postgres would display it. [`None`] when it is `NULL`.
+	pub value: Option<String>,
+}
+
+/// The SQL of a query, and the arguments which are bound to its parameters.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RenderedQuery
+{
+	/// The arguments bound to the parameters of the [`sql`](Self::sql). The first argument is
+	/// bound to `$1`, the second to `$2`, and so on.
+	pub arguments: Vec<BoundArgument>,
+
+	/// The SQL of the query, with parameters (e.g. `$1`) in place of the values being matched.
+	pub sql: String,
+}
+
+/// The number of parameters in the `sql`, which [`QueryBuilder::push_bind`] numbers from `$1`.
+fn count_parameters(sql: &str) -> usize
+{
+	sql.split('$')
+		.skip(1)
+		.filter_map(|s| s.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok())
+		.max()
+		.unwrap_or(0)
+}
+
+/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the `query` (via `connection`), and return the plan.
+pub(super) async fn explain(
+	connection: &Pool<Postgres>,
+	query: &mut QueryBuilder<'_, Postgres>,
+) -> Result<String>
+{
+	let mut built = query.build();
+	let explain = format!("EXPLAIN (ANALYZE, FORMAT JSON) {}", built.sql());
+	let arguments = built.take_arguments().unwrap_or_default();
+
+	// NOTE: the plan is `json`, which has the same representation as `text`.
+	sqlx::query_with(&explain, arguments).fetch_one(connection).await?.try_get_unchecked(0)
+}
+
+/// Return the SQL of the `query` and the arguments bound to it, which are displayed by the
+/// database (via `connection`) without running the `query`.
+pub(super) async fn render(
+	connection: &Pool<Postgres>,
+	query: &mut QueryBuilder<'_, Postgres>,
+) -> Result<RenderedQuery>
+{
+	let mut built = query.build();
+	let sql = built.sql().to_owned();
+	let parameters = count_parameters(&sql);
+
+	if parameters == 0
+	{
+		return Ok(RenderedQuery { arguments: Vec::new(), sql });
+	}
+
+	// NOTE: the same arguments are bound to a query which just displays each of them.
+	let display = format!(
+		"SELECT {}",
+		(1..=parameters)
+			.map(|i| format!("pg_typeof(${i})::text,${i}::text"))
+			.collect::<Vec<_>>()
+			.join(",")
+	);
+
+	let arguments = built.take_arguments().unwrap_or_default();
+	let row = sqlx::query_with(&display, arguments).fetch_one(connection).await?;
+
+	Ok(RenderedQuery {
+		arguments: (0..parameters)
+			.map(|i| -> Result<_> {
+				Ok(BoundArgument { r#type: row.try_get(i * 2)?, value: row.try_get(i * 2 + 1)? })
+			})
+			.collect::<Result<_>>()?,
+		sql,
+	})
+}
+
+/// Implementors of this trait are capable of showing the query which [`Retrievable::retrieve`]
+/// runs, in order to debug and tune it.
+#[async_trait::async_trait]
+pub trait Explainable: Retrievable<Db = Postgres>
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` (via `connection`) on the query which
+	/// [`Retrievable::retrieve`] would run for the `match_condition`, and return the plan as JSON.
+	///
+	/// # Warnings
+	///
+	/// `ANALYZE` means the query is actually run, so that the plan includes how long each step
+	/// took. Only the query which retrieves the entities themselves is explained, and not any which
+	/// [`Retrievable::retrieve`] runs afterwards (e.g. to retrieve [`Location`]s).
+	///
+	/// [`Location`]: clinvoice_schema::Location
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>;
+
+	/// Return the SQL of the query which [`Retrievable::retrieve`] would run for the
+	/// `match_condition`, and the arguments that would be bound to it, without running it.
+	///
+	/// The `connection` is used to display the arguments the same way the database would.
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>;
+}
+
+#[cfg(test)]
+mod tests
+{
+	use clinvoice_match::{
+		MatchJob,
+		MatchLocation,
+		MatchOrganization,
+		MatchOuterLocation,
+		MatchStr,
+		MatchTimesheet,
+	};
+	use pretty_assertions::assert_eq;
+
+	use super::{count_parameters, BoundArgument, Explainable};
+	use crate::schema::{util, PgTimesheet};
+
+	#[test]
+	fn count()
+	{
+		assert_eq!(count_parameters("SELECT 1"), 0);
+		assert_eq!(count_parameters("SELECT $1,$2 WHERE x = $10 OR y = $3"), 10);
+		assert_eq!(count_parameters("SELECT $$ $$"), 0);
+	}
+
+	#[tokio::test]
+	async fn render_and_explain()
+	{
+		let connection = util::connect().await;
+
+		let match_condition = MatchTimesheet {
+			job: MatchJob {
+				client: MatchOrganization {
+					location: MatchLocation {
+						outer: MatchOuterLocation::Some(Box::new(MatchLocation {
+							name: "Earth".to_owned().into(),
+							..Default::default()
+						})),
+						..Default::default()
+					},
+					..Default::default()
+				},
+				..Default::default()
+			},
+			work_notes: MatchStr::Contains("render".into()),
+			..Default::default()
+		};
+
+		let rendered = PgTimesheet::render(&connection, match_condition.clone()).await.unwrap();
+		assert!(rendered.sql.starts_with("WITH RECURSIVE"));
+		assert_eq!(rendered.arguments, [
+			BoundArgument { r#type: "text".into(), value: Some("Earth".into()) },
+			BoundArgument { r#type: "text".into(), value: Some("%render%".into()) },
+		]);
+
+		let plan = PgTimesheet::explain(&connection, match_condition).await.unwrap();
+		assert!(plan.starts_with('['));
+		assert!(plan.contains("\"Actual Total Time\""));
+	}
+}
@@ -0,0 +1,119 @@
+use sqlx::{Acquire, Postgres, Result};
+
+use super::{verify, IndexDrift};
+
+/// A foreign key whose columns are not the leading columns of any index, so that following it
+/// (e.g. in a `JOIN`, or when a referenced row is deleted) requires a scan of the whole table.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UnindexedForeignKey
+{
+	/// The columns of the foreign key, in order.
+	pub columns: Vec<String>,
+
+	/// The name of the foreign key constraint (e.g. `jobs_client_id_fkey`).
+	pub name: String,
+
+	/// The name of the table which the foreign key belongs to.
+	pub table: String,
+}
+
+/// An index which has not been scanned since the statistics of the database were last reset.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UnusedIndex
+{
+	/// The name of the index.
+	pub name: String,
+
+	/// The size of the index on disk, in bytes.
+	pub size: i64,
+
+	/// The name of the table which the index belongs to.
+	pub table: String,
+}
+
+/// The indexes of a database which are missing, and those which are unused.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IndexReport
+{
+	/// Indexes which [`PgSchema::migrate`](super::PgSchema::migrate) would create, but which are
+	/// missing or have a different definition.
+	pub missing: Vec<IndexDrift>,
+
+	/// Foreign keys which are not covered by any index.
+	pub unindexed_foreign_keys: Vec<UnindexedForeignKey>,
+
+	/// Indexes which have never been scanned. Indexes which enforce a `PRIMARY KEY` or `UNIQUE`
+	/// constraint are excluded, since they are required regardless of whether they are scanned.
+	pub unused: Vec<UnusedIndex>,
+}
+
+/// Report the indexes of the current schema which are missing or unused.
+pub(super) async fn report<'connection, Conn>(connection: Conn) -> Result<IndexReport>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	let mut connection = connection.acquire().await?;
+
+	let missing = verify::verify(&mut *connection).await?.indexes;
+
+	let unindexed_foreign_keys = sqlx::query_as::<_, (String, String, Vec<String>)>(
+		"SELECT T.relname::text, K.conname::text, array_agg(A.attname::text ORDER BY U.ordinal)
+		FROM pg_constraint K
+		JOIN pg_class T ON (T.oid = K.conrelid)
+		JOIN pg_namespace N ON (N.oid = T.relnamespace)
+		CROSS JOIN LATERAL unnest(K.conkey) WITH ORDINALITY AS U (attnum, ordinal)
+		JOIN pg_attribute A ON (A.attrelid = K.conrelid AND A.attnum = U.attnum)
+		WHERE N.nspname = current_schema() AND K.contype = 'f' AND NOT EXISTS
+		(
+			SELECT FROM pg_index I
+			WHERE I.indrelid = K.conrelid
+				AND (I.indkey::int2[])[0:cardinality(K.conkey) - 1] @> K.conkey
+		)
+		GROUP BY T.relname, K.conname
+		ORDER BY T.relname, K.conname;",
+	)
+	.fetch_all(&mut *connection)
+	.await?
+	.into_iter()
+	.map(|(table, name, columns)| UnindexedForeignKey { columns, name, table })
+	.collect();
+
+	let unused = sqlx::query_as::<_, (String, String, i64)>(
+		"SELECT S.relname::text, S.indexrelname::text, pg_relation_size(S.indexrelid)
+		FROM pg_stat_user_indexes S
+		JOIN pg_index I ON (I.indexrelid = S.indexrelid)
+		WHERE S.schemaname = current_schema() AND S.idx_scan = 0 AND NOT I.indisunique
+		ORDER BY S.relname, S.indexrelname;",
+	)
+	.fetch_all(&mut *connection)
+	.await?
+	.into_iter()
+	.map(|(table, name, size)| UnusedIndex { name, size, table })
+	.collect();
+
+	Ok(IndexReport { missing, unindexed_foreign_keys, unused })
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+
+	use crate::{schema::util, PgSchema};
+
+	#[tokio::test]
+	async fn index_report()
+	{
+		let connection = util::connect().await;
+
+		PgSchema::migrate(&connection).await.unwrap();
+
+		let report = PgSchema::index_report(&connection).await.unwrap();
+		assert_eq!(report.missing, Vec::new());
+		assert_eq!(report.unindexed_foreign_keys, Vec::new());
+
+		// NOTE: whether an index is used depends on what the other tests have done, but indexes
+		//       which enforce constraints are never reported.
+		assert!(report.unused.iter().all(|i| !i.name.ends_with("_pkey")));
+	}
+}
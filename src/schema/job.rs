@@ -1,14 +1,37 @@
+mod billable_hours;
 mod deletable;
+mod invoice_summary;
 mod job_adapter;
 mod retrievable;
+mod revenue;
 mod updatable;
 
-use clinvoice_adapter::schema::columns::{JobColumns, OrganizationColumns};
-use clinvoice_schema::{Invoice, InvoiceDate, Job};
-use money2::{Decimal, Money};
-use sqlx::{postgres::PgRow, Executor, Postgres, Result, Row};
+use std::collections::HashMap;
 
-use super::{util, PgOrganization};
+pub use billable_hours::BillableHours;
+use clinvoice_adapter::{
+	fmt::sql,
+	schema::columns::{JobColumns, OrganizationColumns},
+};
+use clinvoice_match::{MatchJob, MatchTimesheet};
+use clinvoice_schema::{chrono::Utc, Id, Invoice, InvoiceDate, Job, Location};
+pub use invoice_summary::{InvoiceLineItem, InvoiceSummary};
+use money2::{Currency, Exchange};
+pub use revenue::{Revenue, RevenueDate};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
+
+use super::{
+	exchange_rates::RatesByDate,
+	util,
+	ExchangeRatesSource,
+	Period,
+	PgOrganization,
+	StrComparison,
+};
+
+/// The column of the `jobs` table which stores the [`Currency`](money2::Currency) of the
+/// `invoice_hourly_rate`.
+pub(super) const INVOICE_HOURLY_RATE_CURRENCY: &str = "invoice_hourly_rate_currency";
 
 /// Implementor of the [`JobAdapter`](clinvoice_adapter::schema::JobAdapter) for the
 /// [`Postgres`](sqlx::Postgres) database.
@@ -16,23 +39,140 @@ pub struct PgJob;
 
 impl PgJob
 {
-	pub(super) async fn row_to_view<'connection, Conn, JobColumnName, OrgColumnName>(
-		connection: Conn,
+	/// Retrieve the [`BillableHours`] of every [`Job`], and of every
+	/// [`Employee`](clinvoice_schema::Employee) who worked on each [`Job`], in the
+	/// [`Timesheet`](clinvoice_schema::Timesheet)s that match the `match_condition`.
+	///
+	/// The duration of each [`Timesheet`](clinvoice_schema::Timesheet) is rounded up to the
+	/// [`Job::increment`] by the database, before the hours are added together. Timesheets which
+	/// have not ended yet are not included.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job", rows)))]
+	pub async fn billable_hours(
+		connection: &Pool<Postgres>,
+		match_condition: MatchTimesheet,
+	) -> Result<Vec<BillableHours>>
+	{
+		billable_hours::retrieve(connection, &match_condition).await
+	}
+
+	/// Exchange the [`Invoice::hourly_rate`] of each of the `jobs` into the given `currency`.
+	///
+	/// The exchange rates used are those effective on the date that the [`Invoice`] was
+	/// [issued](InvoiceDate::issued), or today if it has not been issued yet. They are retrieved
+	/// from the `source`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Job", exchange_rates_us))
+	)]
+	pub async fn exchange<Iter>(
+		connection: &Pool<Postgres>,
+		source: &dyn ExchangeRatesSource,
+		jobs: Iter,
+		currency: Currency,
+	) -> Result<Vec<Job>>
+	where
+		Iter: IntoIterator<Item = Job>,
+	{
+		let mut rates = RatesByDate::new(connection, source);
+		let mut exchanged = Vec::new();
+
+		for job in jobs
+		{
+			exchanged.push(Self::exchange_with(&mut rates, job, currency).await?);
+		}
+
+		Ok(exchanged)
+	}
+
+	/// Exchange the `job` into the given `currency` using the `rates` effective on the date its
+	/// [`Invoice`] was [issued](InvoiceDate::issued).
+	///
+	/// # See also
+	///
+	/// * [`PgJob::exchange`]
+	pub(super) async fn exchange_with(
+		rates: &mut RatesByDate<'_, '_>,
+		job: Job,
+		currency: Currency,
+	) -> Result<Job>
+	{
+		let date = job.invoice.date.as_ref().map_or_else(Utc::now, |d| d.issued).naive_utc().date();
+		Ok(job.exchange(currency, rates.on(date).await?))
+	}
+
+	/// Compute what is owed for the [`Job`] with the given `id`, in the currency of its
+	/// [`Invoice::hourly_rate`].
+	///
+	/// Each [`Timesheet`](clinvoice_schema::Timesheet) which has ended is a line item, whose hours
+	/// are rounded up to the [`Job::increment`]. [`Expense`](clinvoice_schema::Expense)s in
+	/// another currency are exchanged by the database using the rates in the `exchange_rates`
+	/// table (see [`PgExchangeRates`](super::PgExchangeRates)) effective on the
+	/// [`Timesheet::time_begin`](clinvoice_schema::Timesheet::time_begin) of each
+	/// [`Expense`](clinvoice_schema::Expense), as in
+	/// [`PgExpenses::exchange`](super::PgExpenses::exchange).
+	///
+	/// # Errors
+	///
+	/// * [`Error::RowNotFound`](sqlx::Error::RowNotFound) if there is no [`Job`] with the `id`.
+	/// * [`Error::ColumnDecode`](sqlx::Error::ColumnDecode) if an
+	///   [`Expense`](clinvoice_schema::Expense) could not be exchanged, because the
+	///   `exchange_rates` table has no rate for its currency on that date.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job", rows)))]
+	pub async fn invoice_summary(connection: &Pool<Postgres>, id: Id) -> Result<InvoiceSummary>
+	{
+		invoice_summary::retrieve(connection, id).await
+	}
+
+	/// Write `(SELECT … FROM … WHERE …)`, which selects the [`Id`] of every [`Job`] that matches
+	/// the `match_condition`, to the `query`.
+	///
+	/// The `query` must begin with the [`PgLocation::query_with_recursive`] of the
+	/// `match_condition.client.location`.
+	///
+	/// [`PgLocation::query_with_recursive`]: super::PgLocation::query_with_recursive
+	pub(super) fn push_matching_ids(query: &mut QueryBuilder<Postgres>, match_condition: &MatchJob)
+	{
+		query.push('(').push(sql::SELECT).push(JobColumns::default().default_scope().id);
+		retrievable::push_from_where(query, match_condition, StrComparison::Exact);
+		query.push(')');
+	}
+
+	/// Retrieve the [`Revenue`] from each client [`Organization`](clinvoice_schema::Organization)
+	/// in each `period`, for the [`Job`]s that match the `match_condition`.
+	///
+	/// The `date` determines which period the revenue of each
+	/// [`Timesheet`](clinvoice_schema::Timesheet) belongs to. It is computed by the database the
+	/// same way as [`PgJob::invoice_summary`], which also describes the errors that may occur.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job", rows)))]
+	pub async fn revenue(
+		connection: &Pool<Postgres>,
+		match_condition: MatchJob,
+		period: Period,
+		date: RevenueDate,
+	) -> Result<Vec<Revenue>>
+	{
+		revenue::retrieve(connection, &match_condition, period, date).await
+	}
+
+	/// Construct a [`Job`] from the `row`.
+	///
+	/// `invoice_hourly_rate_currency` is the name of the column which contains
+	/// [`INVOICE_HOURLY_RATE_CURRENCY`]. The [`Location`] of the client must be among the
+	/// `locations`.
+	pub(super) fn row_to_view<JobColumnName, OrgColumnName>(
 		columns: JobColumns<JobColumnName>,
+		invoice_hourly_rate_currency: &str,
+		locations: &HashMap<Id, Location>,
 		organization_columns: OrganizationColumns<OrgColumnName>,
 		row: &PgRow,
 	) -> Result<Job>
 	where
-		Conn: Executor<'connection, Database = Postgres>,
 		JobColumnName: AsRef<str>,
 		OrgColumnName: AsRef<str>,
 	{
-		let client_fut = PgOrganization::row_to_view(connection, organization_columns, row);
-
-		let amount = row.try_get::<String, _>(columns.invoice_hourly_rate.as_ref()).and_then(
-			|raw_hourly_rate| {
-				raw_hourly_rate.parse::<Decimal>().map_err(|e| util::finance_err_to_sqlx(e.into()))
-			},
+		let hourly_rate = util::money_from(
+			row.try_get(columns.invoice_hourly_rate.as_ref())?,
+			row.try_get(invoice_hourly_rate_currency)?,
 		)?;
 
 		let increment = row.try_get(columns.increment.as_ref()).and_then(util::duration_from)?;
@@ -47,11 +187,80 @@ impl PgJob
 				date: row.try_get::<Option<_>, _>(columns.invoice_date_issued.as_ref()).map(
 					|date| date.map(|d| InvoiceDate { issued: d, paid: invoice_date_paid }),
 				)?,
-				hourly_rate: Money { amount, ..Default::default() },
+				hourly_rate,
 			},
 			notes: row.try_get(columns.notes.as_ref())?,
 			objectives: row.try_get(columns.objectives.as_ref())?,
-			client: client_fut.await?,
+			client: PgOrganization::row_to_view(organization_columns, locations, row)?,
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{JobAdapter, LocationAdapter, OrganizationAdapter};
+	use clinvoice_schema::{
+		chrono::{NaiveDate, TimeZone, Utc},
+		Invoice,
+		InvoiceDate,
+	};
+	use money2::{Currency, Decimal, Money};
+	use pretty_assertions::assert_eq;
+
+	use crate::schema::{
+		exchange_rates,
+		util,
+		PgJob,
+		PgLocation,
+		PgOrganization,
+		StaticExchangeRates,
+	};
+
+	#[tokio::test]
+	async fn exchange()
+	{
+		let connection = util::connect().await;
+		let issued = NaiveDate::from_ymd_opt(1999, 1, 4).unwrap();
+
+		let source = StaticExchangeRates(
+			exchange_rates::exchange_rates_from(issued, [(Currency::Usd, Decimal::new(2, 0))])
+				.unwrap(),
+		);
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Organization".into()).await.unwrap();
+
+		let mut job = PgJob::create(
+			&connection,
+			organization,
+			None,
+			Utc::now(),
+			Duration::from_secs(900),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		)
+		.await
+		.unwrap();
+
+		job.invoice = Invoice {
+			date: Some(InvoiceDate {
+				issued: Utc.from_utc_datetime(&issued.and_hms_opt(12, 0, 0).unwrap()),
+				paid:   None,
+			}),
+			hourly_rate: Money::new(3_00, 2, Currency::Usd),
+		};
+
+		let exchanged = PgJob::exchange(&connection, &source, [job.clone()], Currency::Eur)
+			.await
+			.map(|mut exchanged| exchanged.remove(0))
+			.unwrap();
+		assert_eq!(exchanged.invoice.hourly_rate.amount, Decimal::new(1_50, 2));
+		assert_eq!(exchanged.invoice.hourly_rate.currency, Currency::Eur);
+		assert_eq!(exchanged.id, job.id);
+	}
+}
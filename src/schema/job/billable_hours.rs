@@ -0,0 +1,213 @@
+use clinvoice_adapter::fmt::QueryBuilderExt;
+use clinvoice_match::MatchTimesheet;
+use clinvoice_schema::Id;
+use money2::Decimal;
+use sqlx::{postgres::PgRow, Pool, Postgres, Result, Row};
+
+use crate::schema::{trace, PgLocation, PgTimesheet, StrComparison};
+
+/// The hours which were worked on a [`Job`](clinvoice_schema::Job), before and after rounding each
+/// [`Timesheet`](clinvoice_schema::Timesheet) up to the [`Job::increment`].
+///
+/// [`Job::increment`]: clinvoice_schema::Job::increment
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BillableHours
+{
+	/// The hours worked, after rounding each [`Timesheet`](clinvoice_schema::Timesheet) up to the
+	/// [`Job::increment`](clinvoice_schema::Job::increment).
+	pub billable_hours: Decimal,
+
+	/// The [`Employee`](clinvoice_schema::Employee) who worked the hours, or [`None`] if these are
+	/// the hours that every [`Employee`](clinvoice_schema::Employee) worked on the job.
+	pub employee_id: Option<Id>,
+
+	/// The hours worked, before rounding.
+	pub hours: Decimal,
+
+	/// The [`Job`](clinvoice_schema::Job) which the hours were worked on.
+	pub job_id: Id,
+
+	/// The number of [`Timesheet`](clinvoice_schema::Timesheet)s which the hours were logged in.
+	pub timesheets: i64,
+}
+
+impl BillableHours
+{
+	/// Construct [`BillableHours`] from the `row`.
+	fn from_row(row: &PgRow) -> Result<Self>
+	{
+		Ok(Self {
+			billable_hours: row.try_get("billable_hours")?,
+			employee_id: row.try_get("employee_id")?,
+			hours: row.try_get("hours")?,
+			job_id: row.try_get("job_id")?,
+			timesheets: row.try_get("timesheets")?,
+		})
+	}
+}
+
+/// Retrieve the [`BillableHours`] of each [`Job`](clinvoice_schema::Job), and of each
+/// [`Employee`](clinvoice_schema::Employee) on each [`Job`](clinvoice_schema::Job), in the
+/// [`Timesheet`](clinvoice_schema::Timesheet)s which match the `match_condition`.
+///
+/// The hours of each [`Job`](clinvoice_schema::Job) come before those of its
+/// [`Employee`](clinvoice_schema::Employee)s. [`Timesheet`](clinvoice_schema::Timesheet)s which
+/// have not ended yet are not billable, and so are skipped.
+pub(super) async fn retrieve(
+	connection: &Pool<Postgres>,
+	match_condition: &MatchTimesheet,
+) -> Result<Vec<BillableHours>>
+{
+	let mut query = PgLocation::query_with_recursive(
+		&match_condition.job.client.location,
+		StrComparison::Exact,
+	);
+
+	query.push(
+		" SELECT timesheets.job_id, timesheets.employee_id, count(*) AS timesheets,
+			to_hours(sum(timesheets.time_end - timesheets.time_begin)) AS hours,
+			to_hours(sum(
+				billable_duration(timesheets.time_begin, timesheets.time_end, jobs.increment)
+			)) AS billable_hours
+		FROM timesheets
+		JOIN jobs ON (jobs.id = timesheets.job_id)
+		WHERE timesheets.time_end IS NOT NULL AND timesheets.id IN ",
+	);
+
+	PgTimesheet::push_matching_ids(&mut query, match_condition);
+
+	query.push(
+		" GROUP BY GROUPING SETS
+			((timesheets.job_id), (timesheets.job_id, timesheets.employee_id))
+		ORDER BY timesheets.job_id, timesheets.employee_id NULLS FIRST;",
+	);
+
+	let rows = query.prepare().fetch_all(connection).await?;
+	trace::rows(rows.len());
+	rows.iter().map(BillableHours::from_row).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	};
+	use clinvoice_match::{MatchJob, MatchTimesheet};
+	use clinvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Invoice,
+		Money,
+	};
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+
+	use super::BillableHours;
+	use crate::{
+		schema::{util, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn retrieve()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Organization".into()).await.unwrap();
+
+		// NOTE: created one after the other, so that `employee` has the lower `Id`.
+		let employee =
+			PgEmployee::create(&connection, "My Name".into(), "Employed".into(), "Janitor".into())
+				.await
+				.unwrap();
+		let employee2 = PgEmployee::create(
+			&connection,
+			"Another Gúy".into(),
+			"Employed".into(),
+			"Clerk".into(),
+		)
+		.await
+		.unwrap();
+
+		let job = PgJob::create(
+			&connection,
+			organization,
+			None,
+			Utc.ymd(2022, 07, 01).and_hms(08, 00, 00),
+			Duration::from_secs(900),
+			Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			String::new(),
+			"Round up".into(),
+		)
+		.await
+		.unwrap();
+
+		// {{{
+		let mut transaction = connection.begin().await.unwrap();
+
+		for (employee, time_begin, time_end) in [
+			(&employee, (09, 00), Some((10, 06))),
+			(&employee, (11, 00), Some((11, 30))),
+			(&employee, (13, 00), None),
+			(&employee2, (09, 00), Some((09, 12))),
+		]
+		{
+			PgTimesheet::create(
+				&mut transaction,
+				employee.clone(),
+				Vec::new(),
+				job.clone(),
+				Utc.ymd(2022, 07, 01).and_hms(time_begin.0, time_begin.1, 00),
+				time_end.map(|(h, m)| Utc.ymd(2022, 07, 01).and_hms(h, m, 00)),
+				String::new(),
+			)
+			.await
+			.unwrap();
+		}
+
+		transaction.commit().await.unwrap();
+		// }}}
+
+		assert_eq!(
+			PgJob::billable_hours(&connection, MatchTimesheet {
+				job: MatchJob { id: job.id.into(), ..Default::default() },
+				..Default::default()
+			})
+			.await
+			.unwrap(),
+			[
+				BillableHours {
+					billable_hours: Decimal::new(2_00, 2),
+					employee_id: None,
+					hours: Decimal::new(1_80, 2),
+					job_id: job.id,
+					timesheets: 3,
+				},
+				BillableHours {
+					billable_hours: Decimal::new(1_75, 2),
+					employee_id: Some(employee.id),
+					hours: Decimal::new(1_60, 2),
+					job_id: job.id,
+					timesheets: 2,
+				},
+				BillableHours {
+					billable_hours: Decimal::new(0_25, 2),
+					employee_id: Some(employee2.id),
+					hours: Decimal::new(0_20, 2),
+					job_id: job.id,
+					timesheets: 1,
+				},
+			]
+		);
+	}
+}
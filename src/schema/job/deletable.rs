@@ -11,6 +11,7 @@ impl Deletable for PgJob
 	type Db = Postgres;
 	type Entity = Job;
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job", rows)))]
 	async fn delete<'connection, 'entity, Conn, Iter>(
 		connection: Conn,
 		entities: Iter,
@@ -46,7 +47,7 @@ mod tests
 		Invoice,
 		InvoiceDate,
 	};
-	use money2::{Currency, Exchange, ExchangeRates, Money};
+	use money2::{Currency, Money};
 	use pretty_assertions::assert_eq;
 
 	use crate::schema::{util, PgJob, PgLocation, PgOrganization};
@@ -112,7 +113,6 @@ mod tests
 		assert!(PgOrganization::delete(&connection, [&organization].into_iter()).await.is_err());
 		PgJob::delete(&connection, [&job, &job2].into_iter()).await.unwrap();
 
-		let exchange_rates = ExchangeRates::new().await.unwrap();
 		assert_eq!(
 			PgJob::retrieve(
 				&connection,
@@ -121,7 +121,7 @@ mod tests
 			.await
 			.unwrap()
 			.as_slice(),
-			&[job3.exchange(Default::default(), &exchange_rates)],
+			&[job3],
 		);
 	}
 }
@@ -0,0 +1,300 @@
+use clinvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+use money2::{Decimal, Money};
+use sqlx::{postgres::PgRow, Error, Pool, Postgres, Result, Row};
+
+use crate::schema::{trace, util};
+
+/// The charges for a single [`Timesheet`](clinvoice_schema::Timesheet) on an [`InvoiceSummary`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InvoiceLineItem
+{
+	/// The hours worked, rounded up to the [`Job::increment`](clinvoice_schema::Job::increment).
+	pub billable_hours: Decimal,
+
+	/// The total cost of the [`Expense`](clinvoice_schema::Expense)s of the
+	/// [`Timesheet`](clinvoice_schema::Timesheet).
+	pub expenses: Money,
+
+	/// The [`billable_hours`](Self::billable_hours) multiplied by the
+	/// [`InvoiceSummary::hourly_rate`].
+	pub labor: Money,
+
+	/// When the [`Timesheet`](clinvoice_schema::Timesheet) began.
+	pub time_begin: DateTime<Utc>,
+
+	/// When the [`Timesheet`](clinvoice_schema::Timesheet) ended.
+	pub time_end: DateTime<Utc>,
+
+	/// The [`Id`] of the [`Timesheet`](clinvoice_schema::Timesheet).
+	pub timesheet_id: Id,
+}
+
+impl InvoiceLineItem
+{
+	/// Construct an [`InvoiceLineItem`] from the `row`, whose amounts are in the `currency`.
+	fn from_row(row: &PgRow, currency: &str) -> Result<Self>
+	{
+		Ok(Self {
+			billable_hours: row.try_get("billable_hours")?,
+			expenses: util::money_from(row.try_get("expenses")?, currency)?,
+			labor: util::money_from(row.try_get("labor")?, currency)?,
+			time_begin: row.try_get("time_begin")?,
+			time_end: row.try_get("time_end")?,
+			timesheet_id: row.try_get("id")?,
+		})
+	}
+}
+
+/// What is owed for a [`Job`](clinvoice_schema::Job), in the currency of its
+/// [`Invoice::hourly_rate`](clinvoice_schema::Invoice::hourly_rate).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InvoiceSummary
+{
+	/// The sum of [`InvoiceLineItem::expenses`].
+	pub expenses: Money,
+
+	/// The [`Invoice::hourly_rate`](clinvoice_schema::Invoice::hourly_rate) of the
+	/// [`Job`](clinvoice_schema::Job).
+	pub hourly_rate: Money,
+
+	/// The [`Id`] of the [`Job`](clinvoice_schema::Job).
+	pub job_id: Id,
+
+	/// The sum of [`InvoiceLineItem::labor`].
+	pub labor: Money,
+
+	/// The charges for each [`Timesheet`](clinvoice_schema::Timesheet) of the
+	/// [`Job`](clinvoice_schema::Job), in the order they began.
+	pub line_items: Vec<InvoiceLineItem>,
+
+	/// The [`labor`](Self::labor) plus the [`expenses`](Self::expenses).
+	pub total: Money,
+}
+
+/// Compute the [`InvoiceSummary`] of the [`Job`](clinvoice_schema::Job) with the given `id`.
+///
+/// [`Timesheet`](clinvoice_schema::Timesheet)s which have not ended yet are not billable, and so
+/// are skipped.
+pub(super) async fn retrieve(connection: &Pool<Postgres>, id: Id) -> Result<InvoiceSummary>
+{
+	// NOTE: if any `expenses` could not be exchanged, its line item is `NULL` so that decoding it
+	//       fails instead of silently leaving it out of the sum.
+	let rows = sqlx::query(
+		"SELECT I.*,
+			coalesce(sum(I.labor) OVER (), 0) AS labor_subtotal,
+			sum(I.expenses) OVER () AS expenses_subtotal,
+			coalesce(sum(I.labor) OVER (), 0) + sum(I.expenses) OVER () AS total
+		FROM
+		(
+			SELECT J.invoice_hourly_rate, J.invoice_hourly_rate_currency,
+				T.id, T.time_begin, T.time_end, C.billable_hours, C.labor,
+				CASE WHEN T.id IS NULL THEN 0 ELSE C.expenses END AS expenses
+			FROM jobs J
+			LEFT JOIN timesheets T ON (T.job_id = J.id AND T.time_end IS NOT NULL)
+			LEFT JOIN timesheet_charges C ON (C.timesheet_id = T.id)
+			WHERE J.id = $1
+		) I
+		ORDER BY I.time_begin, I.id;",
+	)
+	.bind(id)
+	.fetch_all(connection)
+	.await?;
+
+	trace::rows(rows.len());
+
+	let first = rows.first().ok_or(Error::RowNotFound)?;
+	let currency: String = first.try_get("invoice_hourly_rate_currency")?;
+
+	// NOTE: a `Job` without any `Timesheet`s still has a row, but it is not a line item.
+	let line_items = if first.try_get::<Option<Id>, _>("id")?.is_some()
+	{
+		rows.iter().map(|row| InvoiceLineItem::from_row(row, &currency)).collect::<Result<_>>()?
+	}
+	else
+	{
+		Vec::new()
+	};
+
+	Ok(InvoiceSummary {
+		expenses: util::money_from(first.try_get("expenses_subtotal")?, &currency)?,
+		hourly_rate: util::money_from(first.try_get("invoice_hourly_rate")?, &currency)?,
+		job_id: id,
+		labor: util::money_from(first.try_get("labor_subtotal")?, &currency)?,
+		line_items,
+		total: util::money_from(first.try_get("total")?, &currency)?,
+	})
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	};
+	use clinvoice_schema::{
+		chrono::{NaiveDate, TimeZone, Utc},
+		Id,
+		Invoice,
+		InvoiceDate,
+	};
+	use money2::{Currency, Decimal, Money};
+	use pretty_assertions::assert_eq;
+	use sqlx::Error;
+
+	use super::{InvoiceLineItem, InvoiceSummary};
+	use crate::{
+		schema::{util, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn retrieve()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		// NOTE: no other test imports exchange rates in February 1999. The rate changes on the day
+		//       that the invoice is issued, but expenses are exchanged at the rate which was
+		//       effective when their timesheet began.
+		PgSchema::import_exchange_rates(&connection, [
+			(NaiveDate::from_ymd_opt(1999, 2, 1).unwrap(), Currency::Usd, Decimal::new(2, 0)),
+			(NaiveDate::from_ymd_opt(1999, 2, 4).unwrap(), Currency::Usd, Decimal::new(4, 0)),
+		])
+		.await
+		.unwrap();
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Organization".into()).await.unwrap();
+		let employee =
+			PgEmployee::create(&connection, "My Name".into(), "Employed".into(), "Janitor".into())
+				.await
+				.unwrap();
+
+		let (job, job2) = futures::try_join!(
+			PgJob::create(
+				&connection,
+				organization.clone(),
+				None,
+				Utc.ymd(1999, 02, 01).and_hms(08, 00, 00),
+				Duration::from_secs(900),
+				Invoice {
+					date: Some(InvoiceDate {
+						issued: Utc.ymd(1999, 02, 04).and_hms(12, 00, 00),
+						paid:   None,
+					}),
+					hourly_rate: Money::new(20_00, 2, Currency::Usd),
+				},
+				String::new(),
+				"Invoice".into(),
+			),
+			PgJob::create(
+				&connection,
+				organization,
+				None,
+				Utc.ymd(1999, 02, 01).and_hms(08, 00, 00),
+				Duration::from_secs(900),
+				Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+				String::new(),
+				"Do not invoice".into(),
+			),
+		)
+		.unwrap();
+
+		// {{{
+		let mut transaction = connection.begin().await.unwrap();
+
+		let timesheet = PgTimesheet::create(
+			&mut transaction,
+			employee.clone(),
+			vec![("Travel".into(), Money::new(10_00, 2, Currency::Usd), "Bus".into())],
+			job.clone(),
+			Utc.ymd(1999, 02, 02).and_hms(09, 00, 00),
+			Some(Utc.ymd(1999, 02, 02).and_hms(10, 06, 00)),
+			String::new(),
+		)
+		.await
+		.unwrap();
+
+		let timesheet2 = PgTimesheet::create(
+			&mut transaction,
+			employee.clone(),
+			vec![
+				("Food".into(), Money::new(4_50, 2, Currency::Eur), "Lunch".into()),
+				("Food".into(), Money::new(4_50, 2, Currency::Eur), "Dinner".into()),
+			],
+			job.clone(),
+			Utc.ymd(1999, 02, 03).and_hms(11, 00, 00),
+			Some(Utc.ymd(1999, 02, 03).and_hms(11, 30, 00)),
+			String::new(),
+		)
+		.await
+		.unwrap();
+
+		PgTimesheet::create(
+			&mut transaction,
+			employee,
+			Vec::new(),
+			job.clone(),
+			Utc.ymd(1999, 02, 04).and_hms(09, 00, 00),
+			None,
+			String::new(),
+		)
+		.await
+		.unwrap();
+
+		transaction.commit().await.unwrap();
+		// }}}
+
+		let usd = |amount| Money { amount, currency: Currency::Usd };
+
+		assert_eq!(PgJob::invoice_summary(&connection, job.id).await.unwrap(), InvoiceSummary {
+			expenses: usd(Decimal::new(28_00, 2)),
+			hourly_rate: usd(Decimal::new(20_00, 2)),
+			job_id: job.id,
+			labor: usd(Decimal::new(35_00, 2)),
+			line_items: vec![
+				InvoiceLineItem {
+					billable_hours: Decimal::new(1_25, 2),
+					expenses: usd(Decimal::new(10_00, 2)),
+					labor: usd(Decimal::new(25_00, 2)),
+					time_begin: timesheet.time_begin,
+					time_end: timesheet.time_end.unwrap(),
+					timesheet_id: timesheet.id,
+				},
+				InvoiceLineItem {
+					billable_hours: Decimal::new(0_50, 2),
+					expenses: usd(Decimal::new(18_00, 2)),
+					labor: usd(Decimal::new(10_00, 2)),
+					time_begin: timesheet2.time_begin,
+					time_end: timesheet2.time_end.unwrap(),
+					timesheet_id: timesheet2.id,
+				},
+			],
+			total: usd(Decimal::new(63_00, 2)),
+		},);
+
+		assert_eq!(PgJob::invoice_summary(&connection, job2.id).await.unwrap(), InvoiceSummary {
+			expenses: usd(Decimal::ZERO),
+			hourly_rate: usd(Decimal::new(20_00, 2)),
+			job_id: job2.id,
+			labor: usd(Decimal::ZERO),
+			line_items: Vec::new(),
+			total: usd(Decimal::ZERO),
+		},);
+
+		assert!(matches!(
+			PgJob::invoice_summary(&connection, Id::MAX).await,
+			Err(Error::RowNotFound)
+		));
+	}
+}
@@ -7,15 +7,15 @@ use clinvoice_schema::{
 	Job,
 	Organization,
 };
-use money2::{Exchange, ExchangeRates};
 use sqlx::{Executor, Postgres, Result};
 
 use super::PgJob;
-use crate::{fmt::DateTimeExt, schema::util};
+use crate::fmt::DateTimeExt;
 
 #[async_trait::async_trait]
 impl JobAdapter for PgJob
 {
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job")))]
 	async fn create<'connection, Conn>(
 		connection: Conn,
 		client: Organization,
@@ -29,16 +29,11 @@ impl JobAdapter for PgJob
 	where
 		Conn: Executor<'connection, Database = Postgres>,
 	{
-		let standardized_rate = ExchangeRates::new()
-			.await
-			.map(|r| invoice.hourly_rate.exchange(Default::default(), &r))
-			.map_err(util::finance_err_to_sqlx)?;
-
 		let row = sqlx::query!(
 			"INSERT INTO jobs
-				(client_id, date_close, date_open, increment, invoice_date_issued, invoice_date_paid, invoice_hourly_rate, notes, objectives)
+				(client_id, date_close, date_open, increment, invoice_date_issued, invoice_date_paid, invoice_hourly_rate, invoice_hourly_rate_currency, notes, objectives)
 			VALUES
-				($1,        $2,         $3,        $4,        $5,                  $6,                $7,                  $8,    $9)
+				($1,        $2,         $3,        $4,        $5,                  $6,                $7,                  $8,                           $9,    $10)
 			RETURNING id;",
 			client.id,
 			date_close,
@@ -46,7 +41,8 @@ impl JobAdapter for PgJob
 			increment as _,
 			invoice.date.as_ref().map(|d| d.issued),
 			invoice.date.as_ref().and_then(|d| d.paid),
-			standardized_rate.amount.to_string() as _,
+			invoice.hourly_rate.amount,
+			invoice.hourly_rate.currency.to_string() as _,
 			notes,
 			objectives,
 		)
@@ -65,7 +61,6 @@ mod tests
 
 	use clinvoice_adapter::schema::{LocationAdapter, OrganizationAdapter};
 	use clinvoice_schema::{chrono::Utc, Currency, Invoice, Money};
-	use money2::{Exchange, ExchangeRates};
 	use pretty_assertions::assert_eq;
 
 	use super::{JobAdapter, PgJob};
@@ -104,6 +99,7 @@ mod tests
 					invoice_date_issued,
 					invoice_date_paid,
 					invoice_hourly_rate,
+					invoice_hourly_rate_currency,
 					notes,
 					objectives
 				FROM jobs
@@ -124,10 +120,8 @@ mod tests
 		assert_eq!(None, row.invoice_date_issued);
 		assert_eq!(None, row.invoice_date_paid);
 		assert_eq!(
-			job.invoice
-				.hourly_rate
-				.exchange(Default::default(), &ExchangeRates::new().await.unwrap()),
-			Money { amount: row.invoice_hourly_rate.parse().unwrap(), ..Default::default() },
+			job.invoice.hourly_rate,
+			util::money_from(row.invoice_hourly_rate, &row.invoice_hourly_rate_currency).unwrap(),
 		);
 		assert_eq!(job.notes, row.notes);
 		assert_eq!(job.objectives, row.objectives);
@@ -2,21 +2,143 @@ use clinvoice_adapter::{
 	fmt::{sql, QueryBuilderExt, TableToSql},
 	schema::columns::{JobColumns, LocationColumns, OrganizationColumns},
 	Retrievable,
-	WriteWhereClause,
+	WriteContext,
 };
 use clinvoice_match::MatchJob;
 use clinvoice_schema::Job;
-use futures::{TryFutureExt, TryStreamExt};
-use money2::{Exchange, ExchangeRates};
-use sqlx::{Pool, Postgres, Result};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
 
-use super::PgJob;
+use super::{PgJob, INVOICE_HOURLY_RATE_CURRENCY};
 use crate::{
 	fmt::PgLocationRecursiveCte,
-	schema::{util, PgLocation},
-	PgSchema,
+	schema::{
+		count,
+		explain,
+		search::{self, SEARCH_VECTOR},
+		sort,
+		trace,
+		write_where_clause::WriteWhere,
+		Countable,
+		Explainable,
+		JobSortKey,
+		Page,
+		Paginatable,
+		Pagination,
+		PgLocation,
+		RenderedQuery,
+		Searchable,
+		Sort,
+		Sortable,
+		StrComparable,
+		StrComparison,
+	},
 };
 
+const COLUMNS: JobColumns<&str> = JobColumns::default();
+
+const ORGANIZATION_COLUMNS_UNIQUE: OrganizationColumns<&str> = OrganizationColumns::unique();
+
+/// Generate the query which retrieves all [`Job`]s that match the `match_condition`, and the
+/// [`WriteContext`] which follows its `WHERE` clause.
+fn query(
+	match_condition: &MatchJob,
+	comparison: StrComparison,
+) -> (QueryBuilder<Postgres>, WriteContext)
+{
+	let mut query = PgLocation::query_with_recursive(&match_condition.client.location, comparison);
+
+	query
+		.push(sql::SELECT)
+		.push_columns(&COLUMNS.default_scope())
+		.push(',')
+		.push(JobColumns::<char>::DEFAULT_ALIAS)
+		.push('.')
+		.push(INVOICE_HOURLY_RATE_CURRENCY)
+		.push_more_columns(
+			&OrganizationColumns::default().default_scope().r#as(ORGANIZATION_COLUMNS_UNIQUE),
+		);
+
+	let context = push_from_where(&mut query, match_condition, comparison);
+
+	(query, context)
+}
+
+/// Write the `FROM` and `WHERE` clauses which select the [`Job`]s that match the
+/// `match_condition` to the `query`, and return the [`WriteContext`] which follows them.
+///
+/// The `query` must begin with [`PgLocation::query_with_recursive`].
+pub(super) fn push_from_where(
+	query: &mut QueryBuilder<Postgres>,
+	match_condition: &MatchJob,
+	comparison: StrComparison,
+) -> WriteContext
+{
+	let columns = COLUMNS.default_scope();
+	let match_location = &match_condition.client.location;
+	let organization_columns = OrganizationColumns::default().default_scope();
+
+	query
+		.push_default_from::<JobColumns<char>>()
+		.push_default_equijoin::<OrganizationColumns<char>, _, _>(
+			organization_columns.id,
+			columns.client_id,
+		)
+		.push_equijoin(
+			PgLocationRecursiveCte::from(match_location),
+			LocationColumns::<char>::DEFAULT_ALIAS,
+			LocationColumns::default().default_scope().id,
+			organization_columns.location_id,
+		);
+
+	comparison.write_where(
+		comparison.write_where(
+			Default::default(),
+			JobColumns::<char>::DEFAULT_ALIAS,
+			match_condition,
+			query,
+		),
+		OrganizationColumns::<char>::DEFAULT_ALIAS,
+		&match_condition.client,
+		query,
+	)
+}
+
+/// The (scoped) column which [`Job`]s are sorted by for the `key`.
+fn sort_column(key: JobSortKey) -> String
+{
+	let columns = COLUMNS.default_scope();
+	match key
+	{
+		JobSortKey::ClientName => OrganizationColumns::default().default_scope().name.to_string(),
+		JobSortKey::DateClose => columns.date_close.to_string(),
+		JobSortKey::DateOpen => columns.date_open.to_string(),
+		JobSortKey::Id => columns.id.to_string(),
+		JobSortKey::InvoiceDateIssued => columns.invoice_date_issued.to_string(),
+		JobSortKey::InvoiceDatePaid => columns.invoice_date_paid.to_string(),
+	}
+}
+
+/// Construct a [`Job`] from each of the `rows`.
+async fn rows_to_views(connection: &Pool<Postgres>, rows: &[PgRow]) -> Result<Vec<Job>>
+{
+	trace::rows(rows.len());
+	let locations =
+		PgLocation::retrieve_for_rows(connection, ORGANIZATION_COLUMNS_UNIQUE.location_id, rows)
+			.await?;
+
+	rows.iter()
+		.map(|row| {
+			PgJob::row_to_view(
+				COLUMNS,
+				INVOICE_HOURLY_RATE_CURRENCY,
+				&locations,
+				ORGANIZATION_COLUMNS_UNIQUE,
+				row,
+			)
+		})
+		.collect()
+}
+
 /// Implementors of this trait are capable of being retrieved from a [`Database`].
 #[async_trait::async_trait]
 impl Retrievable for PgJob
@@ -34,57 +156,168 @@ impl Retrievable for PgJob
 		match_condition: Self::Match,
 	) -> Result<Vec<Self::Entity>>
 	{
-		const COLUMNS: JobColumns<&str> = JobColumns::default();
+		Self::retrieve_comparing(connection, match_condition, StrComparison::Exact).await
+	}
+}
 
-		const ORGANIZATION_COLUMNS_UNIQUE: OrganizationColumns<&str> =
-			OrganizationColumns::unique();
+#[async_trait::async_trait]
+impl StrComparable for PgJob
+{
+	/// Retrieve all [`Job`]s (via `connection`) that match the `match_condition`, comparing
+	/// strings according to the `comparison`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Job", locations, rows))
+	)]
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, comparison);
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
 
+#[async_trait::async_trait]
+impl Paginatable for PgJob
+{
+	/// Retrieve the [`Page`] of [`Job`]s (via `connection`) that match the `match_condition` which
+	/// is described by the `pagination`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Job", locations, rows))
+	)]
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>
+	{
 		let columns = COLUMNS.default_scope();
-		let exchange_rates_fut = ExchangeRates::new().map_err(util::finance_err_to_sqlx);
-		let match_location = match_condition.client.location.clone();
-		let mut query = PgLocation::query_with_recursive(&match_location);
-		let organization_columns = OrganizationColumns::default().default_scope();
-
-		query
-			.push(sql::SELECT)
-			.push_columns(&columns)
-			.push_more_columns(&organization_columns.r#as(ORGANIZATION_COLUMNS_UNIQUE))
-			.push_default_from::<JobColumns<char>>()
-			.push_default_equijoin::<OrganizationColumns<char>, _, _>(
-				organization_columns.id,
-				columns.client_id,
-			)
-			.push_equijoin(
-				PgLocationRecursiveCte::from(&match_location),
-				LocationColumns::<char>::DEFAULT_ALIAS,
-				LocationColumns::default().default_scope().id,
-				organization_columns.location_id,
-			);
+		let (mut query, context) = query(&match_condition, StrComparison::Exact);
 
-		let exchanged_condition = exchange_rates_fut
-			.await
-			.map(|rates| match_condition.exchange(Default::default(), &rates))?;
-
-		PgSchema::write_where_clause(
-			PgSchema::write_where_clause(
-				Default::default(),
-				JobColumns::<char>::DEFAULT_ALIAS,
-				&exchanged_condition,
-				&mut query,
-			),
-			OrganizationColumns::<char>::DEFAULT_ALIAS,
-			&exchanged_condition.client,
-			&mut query,
+		pagination.push_where(&mut query, context, columns.id, None)?;
+		pagination.push_order_limit(&mut query, columns.id, None)?;
+
+		let mut rows = query.prepare().fetch_all(connection).await?;
+		let next = pagination.next(&mut rows, COLUMNS.id, None)?;
+
+		Ok(Page { entities: rows_to_views(connection, &rows).await?, next })
+	}
+}
+
+#[async_trait::async_trait]
+impl Sortable for PgJob
+{
+	type SortKey = JobSortKey;
+
+	/// Retrieve all [`Job`]s (via `connection`) that match the `match_condition`, ordered by
+	/// the `sort`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Job", locations, rows))
+	)]
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		sort::push_order_by(&mut query, sort, sort_column, COLUMNS.default_scope().id);
+
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Countable for PgJob
+{
+	/// Count the [`Job`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job")))]
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>
+	{
+		let mut query = PgLocation::query_with_recursive(
+			&match_condition.client.location,
+			StrComparison::Exact,
 		);
 
-		query
-			.prepare()
-			.fetch(connection)
-			.and_then(|row| async move {
-				Self::row_to_view(connection, COLUMNS, ORGANIZATION_COLUMNS_UNIQUE, &row).await
-			})
-			.try_collect()
-			.await
+		query.push(sql::SELECT).push(count::COUNT);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+
+	/// Check whether there are any [`Job`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job")))]
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>
+	{
+		let mut query = PgLocation::query_with_recursive(
+			&match_condition.client.location,
+			StrComparison::Exact,
+		);
+
+		query.push(sql::SELECT).push(count::EXISTS);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+		query.push(')');
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+}
+
+#[async_trait::async_trait]
+impl Searchable for PgJob
+{
+	/// Retrieve all [`Job`]s (via `connection`) whose `objectives` or `notes` match the `text` and
+	/// that match the `match_condition`, with the most relevant first.
+	///
+	/// Matches in the `objectives` are more relevant than those in the `notes`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Job", locations, rows))
+	)]
+	async fn search(
+		connection: &Pool<Postgres>,
+		text: &str,
+		match_condition: Self::Match,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let search_vector = format!("{}.{SEARCH_VECTOR}", JobColumns::<char>::DEFAULT_ALIAS);
+		let (mut query, context) = query(&match_condition, StrComparison::Exact);
+
+		search::push_where(&mut query, context, &search_vector, text);
+		search::push_order_by_rank(&mut query, &search_vector, text, COLUMNS.default_scope().id);
+
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Explainable for PgJob
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the query which retrieves the
+	/// [`Job`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job")))]
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::explain(connection, &mut query).await
+	}
+
+	/// Render the query which retrieves the [`Job`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job")))]
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::render(connection, &mut query).await
 	}
 }
 
@@ -106,10 +339,9 @@ mod tests
 		InvoiceDate,
 		Money,
 	};
-	use money2::{Exchange, ExchangeRates};
 	use pretty_assertions::assert_eq;
 
-	use crate::schema::{util, PgJob, PgLocation, PgOrganization};
+	use crate::schema::{util, PgJob, PgLocation, PgOrganization, Searchable};
 
 	#[tokio::test]
 	async fn retrieve()
@@ -188,11 +420,25 @@ mod tests
 		)
 		.unwrap();
 
-		let exchange_rates = ExchangeRates::new().await.unwrap();
+		assert_eq!(PgJob::retrieve(&connection, job.id.into()).await.unwrap().as_slice(), &[
+			job.clone()
+		]);
 
-		assert_eq!(PgJob::retrieve(&connection, job.id.into()).await.unwrap().as_slice(), &[job
-			.clone()
-			.exchange(Default::default(), &exchange_rates)],);
+		assert_eq!(
+			PgJob::retrieve(&connection, MatchJob {
+				date_open: Match::InRange(
+					Utc.ymd(1990, 01, 01).and_hms(00, 00, 00).naive_utc(),
+					Utc.ymd(2000, 01, 01).and_hms(00, 00, 00).naive_utc(),
+				),
+				id: Match::Or(vec![job.id.into(), job2.id.into(), job3.id.into(), job4.id.into()]),
+				increment: Match::EqualTo(Duration::from_secs(300).into()),
+				..Default::default()
+			})
+			.await
+			.unwrap()
+			.as_slice(),
+			&[job.clone()],
+		);
 
 		assert_eq!(
 			PgJob::retrieve(&connection, MatchJob {
@@ -204,12 +450,7 @@ mod tests
 			.unwrap()
 			.into_iter()
 			.collect::<HashSet<_>>(),
-			[
-				job2.exchange(Default::default(), &exchange_rates),
-				job3.exchange(Default::default(), &exchange_rates),
-			]
-			.into_iter()
-			.collect::<HashSet<_>>(),
+			[job2.clone(), job3.clone()].into_iter().collect::<HashSet<_>>(),
 		);
 
 		assert_eq!(
@@ -222,12 +463,91 @@ mod tests
 			.unwrap()
 			.into_iter()
 			.collect::<HashSet<_>>(),
-			[
-				job.exchange(Default::default(), &exchange_rates),
-				job4.exchange(Default::default(), &exchange_rates),
-			]
-			.into_iter()
-			.collect::<HashSet<_>>(),
+			[job, job4].into_iter().collect::<HashSet<_>>(),
+		);
+
+		// The amount of money is only compared with amounts of the same currency
+		assert_eq!(
+			PgJob::retrieve(&connection, MatchJob {
+				id: Match::Or(vec![job2.id.into(), job3.id.into()]),
+				invoice: MatchInvoice {
+					hourly_rate: Match::GreaterThan(Money::new(10_00, 2, Currency::Jpy)),
+					..Default::default()
+				},
+				..Default::default()
+			})
+			.await
+			.unwrap()
+			.as_slice(),
+			&[job2.clone()],
+		);
+
+		// `Any` matches every amount, whether it is alone in an `And` or beside others in an `Or`
+		for hourly_rate in [
+			Match::And(vec![Match::Any]),
+			Match::Or(vec![Match::Any, Match::GreaterThan(Money::new(10_00, 2, Currency::Jpy))]),
+		]
+		{
+			assert_eq!(
+				PgJob::retrieve(&connection, MatchJob {
+					id: Match::Or(vec![job2.id.into(), job3.id.into()]),
+					invoice: MatchInvoice { hourly_rate, ..Default::default() },
+					..Default::default()
+				})
+				.await
+				.unwrap()
+				.into_iter()
+				.collect::<HashSet<_>>(),
+				[job2.clone(), job3.clone()].into_iter().collect::<HashSet<_>>(),
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn search()
+	{
+		let connection = util::connect().await;
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Organization".into()).await.unwrap();
+
+		let create = |notes: &str, objectives: &str| {
+			PgJob::create(
+				&connection,
+				organization.clone(),
+				None,
+				Utc::now(),
+				Duration::from_secs(900),
+				Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+				notes.into(),
+				objectives.into(),
+			)
+		};
+
+		let (job, job2, job3) = futures::try_join!(
+			create("", "Migrate the database"),
+			create("Weekly meeting about the database migration", "Plan the database migration"),
+			create("The database is fine", "Paint the fence"),
+		)
+		.unwrap();
+
+		let match_condition = MatchJob {
+			id: Match::Or(vec![job.id.into(), job2.id.into(), job3.id.into()]),
+			..Default::default()
+		};
+
+		// `job2` mentions the migration more than `job`, and `job3` does not mention it at all
+		assert_eq!(
+			PgJob::search(&connection, "database migration", match_condition.clone())
+				.await
+				.unwrap(),
+			[job2, job],
+		);
+
+		assert_eq!(
+			PgJob::search(&connection, "database -migration", match_condition).await.unwrap(),
+			[job3],
 		);
 	}
 }
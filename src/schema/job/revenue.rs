@@ -0,0 +1,281 @@
+use clinvoice_adapter::fmt::QueryBuilderExt;
+use clinvoice_match::MatchJob;
+use clinvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+use money2::Money;
+use sqlx::{postgres::PgRow, Pool, Postgres, Result, Row};
+
+use crate::schema::{trace, util, Period, PgJob, PgLocation, StrComparison};
+
+/// Which date of a [`Timesheet`](clinvoice_schema::Timesheet) decides the [`Period`] that its
+/// [`Revenue`] belongs to.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum RevenueDate
+{
+	/// [`InvoiceDate::issued`](clinvoice_schema::InvoiceDate::issued), so that revenue is counted
+	/// when it is billed. [`Job`](clinvoice_schema::Job)s which have not been invoiced yet are
+	/// skipped.
+	InvoiceDateIssued,
+
+	/// [`Timesheet::time_begin`](clinvoice_schema::Timesheet::time_begin), so that revenue is
+	/// counted when it is earned, whether or not it has been invoiced yet.
+	TimeBegin,
+}
+
+impl RevenueDate
+{
+	/// The (scoped) column which contains this date.
+	const fn column(self) -> &'static str
+	{
+		match self
+		{
+			Self::InvoiceDateIssued => "J.invoice_date_issued",
+			Self::TimeBegin => "T.time_begin",
+		}
+	}
+}
+
+/// The revenue from a client [`Organization`](clinvoice_schema::Organization) in a single
+/// [`Period`].
+///
+/// Every amount is in the same currency, since [`Job`](clinvoice_schema::Job)s billed in different
+/// currencies are not added together.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Revenue
+{
+	/// The total cost of the [`Expense`](clinvoice_schema::Expense)s.
+	pub expenses: Money,
+
+	/// The hours worked (rounded up to the [`Job::increment`](clinvoice_schema::Job::increment))
+	/// multiplied by the [`Invoice::hourly_rate`](clinvoice_schema::Invoice::hourly_rate).
+	pub labor: Money,
+
+	/// The [`Id`] of the client [`Organization`](clinvoice_schema::Organization).
+	pub organization_id: Id,
+
+	/// When the [`Period`] began.
+	pub period: DateTime<Utc>,
+
+	/// The [`labor`](Self::labor) plus the [`expenses`](Self::expenses).
+	pub total: Money,
+}
+
+impl Revenue
+{
+	/// Construct [`Revenue`] from the `row`.
+	fn from_row(row: &PgRow) -> Result<Self>
+	{
+		let currency: String = row.try_get("currency")?;
+		Ok(Self {
+			expenses: util::money_from(row.try_get("expenses")?, &currency)?,
+			labor: util::money_from(row.try_get("labor")?, &currency)?,
+			organization_id: row.try_get("organization_id")?,
+			period: row.try_get("period")?,
+			total: util::money_from(row.try_get("total")?, &currency)?,
+		})
+	}
+}
+
+/// Retrieve the [`Revenue`] from each client [`Organization`](clinvoice_schema::Organization) in
+/// each `period`, for the [`Job`](clinvoice_schema::Job)s which match the `match_condition`.
+///
+/// The rows are sorted by [`Revenue::period`], then [`Revenue::organization_id`], then currency.
+/// [`Timesheet`](clinvoice_schema::Timesheet)s which have not ended yet are not billable, and so
+/// are skipped.
+pub(super) async fn retrieve(
+	connection: &Pool<Postgres>,
+	match_condition: &MatchJob,
+	period: Period,
+	date: RevenueDate,
+) -> Result<Vec<Revenue>>
+{
+	let mut query =
+		PgLocation::query_with_recursive(&match_condition.client.location, StrComparison::Exact);
+
+	// NOTE: if any expense could not be exchanged, the `expenses` are `NULL` so that decoding them
+	//       fails instead of silently leaving the expense out of the sum.
+	query.push(
+		" SELECT J.client_id AS organization_id, C.currency, P.period, sum(C.labor) AS labor,
+			CASE WHEN count(*) = count(C.expenses) THEN sum(C.expenses) END AS expenses,
+			sum(C.labor) + CASE WHEN count(*) = count(C.expenses) THEN sum(C.expenses) END AS total
+		FROM jobs J
+		JOIN timesheets T ON (T.job_id = J.id)
+		JOIN timesheet_charges C ON (C.timesheet_id = T.id)
+		CROSS JOIN LATERAL (SELECT ",
+	);
+
+	period.push_date_trunc(&mut query, date.column());
+	query.push(" AS period) P WHERE P.period IS NOT NULL AND J.id IN ");
+
+	PgJob::push_matching_ids(&mut query, match_condition);
+
+	query.push(
+		" GROUP BY P.period, J.client_id, C.currency
+		ORDER BY P.period, J.client_id, C.currency;",
+	);
+
+	let rows = query.prepare().fetch_all(connection).await?;
+	trace::rows(rows.len());
+	rows.iter().map(Revenue::from_row).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	};
+	use clinvoice_match::{Match, MatchJob};
+	use clinvoice_schema::{
+		chrono::{self, TimeZone, Utc},
+		Invoice,
+		InvoiceDate,
+	};
+	use money2::{Currency, Money};
+	use pretty_assertions::assert_eq;
+
+	use super::{Revenue, RevenueDate};
+	use crate::{
+		schema::{util, Period, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn retrieve()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Organization".into()).await.unwrap();
+		let employee =
+			PgEmployee::create(&connection, "My Name".into(), "Employed".into(), "Janitor".into())
+				.await
+				.unwrap();
+
+		let hourly_rate = Money::new(20_00, 2, Currency::Usd);
+		let (job, job2, job3) = futures::try_join!(
+			PgJob::create(
+				&connection,
+				organization.clone(),
+				None,
+				Utc.ymd(2021, 01, 01).and_hms(08, 00, 00),
+				Duration::from_secs(900),
+				Invoice {
+					date: Some(InvoiceDate {
+						issued: Utc.ymd(2021, 03, 15).and_hms(12, 00, 00),
+						paid:   None,
+					}),
+					hourly_rate,
+				},
+				String::new(),
+				"Invoiced in March".into(),
+			),
+			PgJob::create(
+				&connection,
+				organization.clone(),
+				None,
+				Utc.ymd(2021, 04, 01).and_hms(08, 00, 00),
+				Duration::from_secs(900),
+				Invoice {
+					date: Some(InvoiceDate {
+						issued: Utc.ymd(2021, 05, 02).and_hms(12, 00, 00),
+						paid:   None,
+					}),
+					hourly_rate,
+				},
+				String::new(),
+				"Invoiced in May".into(),
+			),
+			PgJob::create(
+				&connection,
+				organization.clone(),
+				None,
+				Utc.ymd(2021, 04, 01).and_hms(08, 00, 00),
+				Duration::from_secs(900),
+				Invoice { date: None, hourly_rate },
+				String::new(),
+				"Not invoiced".into(),
+			),
+		)
+		.unwrap();
+
+		// {{{
+		let mut transaction = connection.begin().await.unwrap();
+
+		for (job, time_begin, minutes, expenses) in [
+			(&job, Utc.ymd(2021, 01, 10).and_hms(09, 00, 00), 60, vec![(
+				"Travel".into(),
+				Money::new(5_00, 2, Currency::Usd),
+				"Bus".into(),
+			)]),
+			(&job, Utc.ymd(2021, 02, 20).and_hms(09, 00, 00), 30, Vec::new()),
+			(&job2, Utc.ymd(2021, 04, 30).and_hms(09, 00, 00), 60, Vec::new()),
+			(&job3, Utc.ymd(2021, 04, 01).and_hms(09, 00, 00), 60, Vec::new()),
+		]
+		{
+			PgTimesheet::create(
+				&mut transaction,
+				employee.clone(),
+				expenses,
+				job.clone(),
+				time_begin,
+				Some(time_begin + chrono::Duration::minutes(minutes)),
+				String::new(),
+			)
+			.await
+			.unwrap();
+		}
+
+		transaction.commit().await.unwrap();
+		// }}}
+
+		let match_condition = MatchJob {
+			id: Match::Or(vec![job.id.into(), job2.id.into(), job3.id.into()]),
+			..Default::default()
+		};
+
+		let usd = |amount| Money::new(amount, 2, Currency::Usd);
+		let revenue = |period, labor, expenses| Revenue {
+			expenses: usd(expenses),
+			labor: usd(labor),
+			organization_id: organization.id,
+			period,
+			total: usd(labor + expenses),
+		};
+
+		assert_eq!(
+			PgJob::revenue(
+				&connection,
+				match_condition.clone(),
+				Period::Month,
+				RevenueDate::InvoiceDateIssued,
+			)
+			.await
+			.unwrap(),
+			[
+				revenue(Utc.ymd(2021, 03, 01).and_hms(00, 00, 00), 30_00, 5_00),
+				revenue(Utc.ymd(2021, 05, 01).and_hms(00, 00, 00), 20_00, 0),
+			]
+		);
+
+		assert_eq!(
+			PgJob::revenue(&connection, match_condition, Period::Quarter, RevenueDate::TimeBegin)
+				.await
+				.unwrap(),
+			[
+				revenue(Utc.ymd(2021, 01, 01).and_hms(00, 00, 00), 30_00, 5_00),
+				revenue(Utc.ymd(2021, 04, 01).and_hms(00, 00, 00), 40_00, 0),
+			]
+		);
+	}
+}
@@ -3,16 +3,10 @@ use clinvoice_schema::{
 	chrono::{DateTime, Utc},
 	Job,
 };
-use futures::TryFutureExt;
-use money2::{Exchange, ExchangeRates};
 use sqlx::{Postgres, Result, Transaction};
 
-use super::PgJob;
-use crate::{
-	fmt::DateTimeExt,
-	schema::{util, PgOrganization},
-	PgSchema,
-};
+use super::{PgJob, INVOICE_HOURLY_RATE_CURRENCY};
+use crate::{fmt::DateTimeExt, schema::PgOrganization, PgSchema};
 
 #[async_trait::async_trait]
 impl Updatable for PgJob
@@ -20,6 +14,7 @@ impl Updatable for PgJob
 	type Db = Postgres;
 	type Entity = Job;
 
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Job", rows)))]
 	async fn update<'entity, Iter>(
 		connection: &mut Transaction<Self::Db>,
 		entities: Iter,
@@ -36,7 +31,6 @@ impl Updatable for PgJob
 			return Ok(());
 		}
 
-		let exchange_rates = ExchangeRates::new().map_err(util::finance_err_to_sqlx).await?;
 		PgSchema::update(connection, JobColumns::default(), |query| {
 			query.push_values(peekable_entities, |mut q, e| {
 				q.push_bind(e.client.id)
@@ -51,19 +45,20 @@ impl Updatable for PgJob
 					_ => q.push_bind(None::<DateTime<Utc>>).push_bind(None::<DateTime<Utc>>),
 				};
 
-				q.push_bind(
-					e.invoice
-						.hourly_rate
-						.exchange(Default::default(), &exchange_rates)
-						.amount
-						.to_string(),
-				)
-				.push_bind(&e.notes)
-				.push_bind(&e.objectives);
+				q.push_bind(e.invoice.hourly_rate.amount)
+					.push_bind(&e.notes)
+					.push_bind(&e.objectives);
 			});
 		})
 		.await?;
 
+		PgSchema::update_currency::<_, JobColumns<char>>(
+			connection,
+			INVOICE_HOURLY_RATE_CURRENCY,
+			entities.clone().map(|e| (e.id, e.invoice.hourly_rate.currency)),
+		)
+		.await?;
+
 		PgOrganization::update(connection, entities.map(|e| &e.client)).await
 	}
 }
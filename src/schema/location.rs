@@ -4,18 +4,19 @@ mod retrievable;
 mod updatable;
 
 use core::fmt::Display;
+use std::collections::{HashMap, HashSet};
 
 use clinvoice_adapter::{
 	fmt::{sql, QueryBuilderExt, SnakeCase, TableToSql},
 	schema::columns::LocationColumns,
-	WriteWhereClause,
 };
 use clinvoice_match::{Match, MatchLocation, MatchOption, MatchOuterLocation};
 use clinvoice_schema::{Id, Location};
-use futures::{future, TryFutureExt, TryStreamExt};
-use sqlx::{Error, Executor, Postgres, QueryBuilder, Result, Row};
+use futures::{TryFutureExt, TryStreamExt};
+use sqlx::{postgres::PgRow, Error, Executor, Postgres, QueryBuilder, Result, Row};
 
-use crate::{fmt::PgLocationRecursiveCte, PgSchema};
+use super::{trace, write_where_clause::WriteWhere, StrComparison};
+use crate::fmt::PgLocationRecursiveCte;
 
 const COLUMNS: LocationColumns<&str> = LocationColumns::default();
 
@@ -25,8 +26,8 @@ pub struct PgLocation;
 
 impl PgLocation
 {
-	/// Generate a `WITH RECURSIVE` statement given some `match_condition`.
-	///
+	/// Generate a `WITH RECURSIVE` statement given some `match_condition`, comparing strings
+	/// according to the `comparison`.
 	///
 	/// Contains a `location` identifier, plus a `location_outer` (plus `location_outer_outer`) for
 	/// each `match_condition.outer` (`match_condition.outer.outer`, etc.) as well as a
@@ -36,11 +37,15 @@ impl PgLocation
 	/// # See also
 	///
 	/// * [`PgLocationRecursiveCte`] for more about the identifiers.
-	pub(super) fn query_with_recursive(match_condition: &MatchLocation) -> QueryBuilder<Postgres>
+	pub(super) fn query_with_recursive(
+		match_condition: &MatchLocation,
+		comparison: StrComparison,
+	) -> QueryBuilder<Postgres>
 	{
 		/// Generate one expression in a recursive CTE.
 		fn generate_expression<T, Outer>(
 			query: &mut QueryBuilder<Postgres>,
+			comparison: StrComparison,
 			ident: PgLocationRecursiveCte<T, Outer>,
 			match_condition: &MatchLocation,
 		) where
@@ -66,11 +71,11 @@ impl PgLocation
 				query.push_equijoin(prev, alias, outer_columns.id, columns.outer_id);
 			}
 
-			PgSchema::write_where_clause(
-				PgSchema::write_where_clause(
+			comparison.write_where(
+				comparison.write_where(
 					match match_condition.outer
 					{
-						MatchOuterLocation::None => PgSchema::write_where_clause(
+						MatchOuterLocation::None => comparison.write_where(
 							Default::default(),
 							outer_columns.outer_id,
 							&MatchOption::<Id>::None,
@@ -96,6 +101,7 @@ impl PgLocation
 					query.push(',');
 					generate_expression(
 						query,
+						comparison,
 						// HACK: remove `.to_string()` after rust-lang/rust#39959
 						PgLocationRecursiveCte::outer(ident.to_string()),
 						outer,
@@ -135,72 +141,121 @@ impl PgLocation
 
 		let mut query = QueryBuilder::new(sql::WITH_RECURSIVE);
 
-		generate_expression(&mut query, PgLocationRecursiveCte::new(), match_condition);
+		generate_expression(&mut query, comparison, PgLocationRecursiveCte::new(), match_condition);
 
 		query.push(' ');
 		query
 	}
 
-	/// Construct a [`Location`], also constructing all outer [`Location`]s, and return it.
-	pub(super) async fn retrieve_by_id<'connection, Conn>(
+	/// Construct the [`Location`] with each of the `ids`, also constructing all outer
+	/// [`Location`]s, and return them keyed by their [`Id`].
+	///
+	/// Every ancestor is retrieved with a single query, and each distinct [`Location`] is only
+	/// constructed once, no matter how many times it is an outer [`Location`].
+	pub(super) async fn retrieve_by_ids<'connection, Conn, Iter>(
 		connection: Conn,
-		id: Id,
-	) -> Result<Location>
+		ids: Iter,
+	) -> Result<HashMap<Id, Location>>
 	where
 		Conn: Executor<'connection, Database = Postgres>,
+		Iter: IntoIterator<Item = Id>,
 	{
-		const SOURCE: &str = "this column in `locations` must be non-null";
-		sqlx::query!(
-			r#"WITH RECURSIVE location_view AS
+		/// Construct the [`Location`] with the given `id` from the `views`, using (and adding to)
+		/// the `built` [`Location`]s.
+		fn build(
+			id: Id,
+			views: &HashMap<Id, (String, Option<Id>)>,
+			built: &mut HashMap<Id, Location>,
+			depth: usize,
+		) -> Result<Location>
+		{
+			if let Some(location) = built.get(&id)
+			{
+				return Ok(location.clone());
+			}
+
+			// NOTE: a `Location` cannot have more outer `Location`s than there are `Location`s.
+			if depth > views.len()
+			{
+				return Err(Error::Decode(
+					format!("`Location` {id} is (transitively) outside of itself").into(),
+				));
+			}
+
+			let (name, outer_id) = views.get(&id).ok_or(Error::RowNotFound)?;
+			let location = Location {
+				id,
+				name: name.clone(),
+				outer: outer_id
+					.map(|outer| build(outer, views, built, depth + 1).map(Box::new))
+					.transpose()?,
+			};
+
+			built.insert(id, location.clone());
+			Ok(location)
+		}
+
+		let ids: Vec<Id> = ids.into_iter().collect();
+		let views: HashMap<Id, (String, Option<Id>)> = sqlx::query_as(
+			"WITH RECURSIVE location_view AS
 			(
-				SELECT id, name, outer_id, 0 as "order" FROM locations WHERE id = $1
+				SELECT id, name, outer_id FROM locations WHERE id = ANY($1)
 				UNION
-				SELECT L.id, L.name, L.outer_id, V."order" + 1 FROM locations L JOIN location_view V ON (L.id = V.outer_id)
-			) SELECT * FROM location_view ORDER BY "order" DESC;"#,
-			id,
+				SELECT L.id, L.name, L.outer_id FROM locations L JOIN location_view V ON (L.id = V.outer_id)
+			) SELECT id, name, outer_id FROM location_view;",
 		)
+		.bind(&ids)
 		.fetch(connection)
-		.try_fold(None, |previous: Option<Location>, view| {
-			future::ok(Some(Location {
-				id:    match view.id
-				{
-					Some(id) => id,
-					_ =>
-					{
-						return future::err(Error::ColumnDecode {
-							index:  "name".into(),
-							source: SOURCE.into(),
-						})
-					},
-				},
-				name:  match view.name
-				{
-					Some(n) => n,
-					_ =>
-					{
-						return future::err(Error::ColumnDecode {
-							index:  "name".into(),
-							source: SOURCE.into(),
-						})
-					},
-				},
-				outer: previous.map(Box::new),
-			}))
-		})
-		.await
-		.and_then(|v| v.ok_or(Error::RowNotFound))
+		.map_ok(|(id, name, outer_id)| (id, (name, outer_id)))
+		.try_collect()
+		.await?;
+
+		let mut built = HashMap::with_capacity(views.len());
+		for id in ids
+		{
+			build(id, &views, &mut built, 0)?;
+		}
+
+		trace::locations(built.len());
+		Ok(built)
+	}
+
+	/// [Retrieve](PgLocation::retrieve_by_ids) the [`Location`] of every (non-null) `location_id`
+	/// column in the `rows`.
+	pub(super) async fn retrieve_for_rows<'connection, Conn>(
+		connection: Conn,
+		location_id: &str,
+		rows: &[PgRow],
+	) -> Result<HashMap<Id, Location>>
+	where
+		Conn: Executor<'connection, Database = Postgres>,
+	{
+		let ids = rows
+			.iter()
+			.filter_map(|row| row.try_get::<Option<Id>, _>(location_id).transpose())
+			.collect::<Result<HashSet<_>>>()?;
+
+		Self::retrieve_by_ids(connection, ids).await
+	}
+
+	/// Get the [`Location`] with the given `id` from some `locations` which were
+	/// [retrieved](PgLocation::retrieve_by_ids) beforehand.
+	pub(super) fn get(locations: &HashMap<Id, Location>, id: Id) -> Result<Location>
+	{
+		locations.get(&id).cloned().ok_or(Error::RowNotFound)
 	}
 
 	/// Retrieve a [`Match`] which will match all of the [`Id`]s of the [`Location`]s which match
-	/// the `match_condition`.
+	/// the `match_condition`, comparing strings according to the `comparison`.
 	pub(super) async fn retrieve_matching_ids<'connection, Conn>(
 		connection: Conn,
 		match_condition: &MatchLocation,
+		comparison: StrComparison,
 	) -> Result<Match<Id>>
 	where
 		Conn: Executor<'connection, Database = Postgres>,
 	{
-		let mut query = Self::query_with_recursive(match_condition);
+		let mut query = Self::query_with_recursive(match_condition, comparison);
 
 		query
 			.push(sql::SELECT)
@@ -217,3 +272,41 @@ impl PgLocation
 			.await
 	}
 }
+
+#[cfg(test)]
+mod tests
+{
+	use clinvoice_adapter::schema::LocationAdapter;
+	use pretty_assertions::assert_eq;
+
+	use super::PgLocation;
+	use crate::schema::util;
+
+	#[tokio::test]
+	async fn retrieve_by_ids()
+	{
+		let connection = util::connect().await;
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let usa = PgLocation::create(&connection, "USA".into(), Some(earth.clone())).await.unwrap();
+
+		let (arizona, utah) = futures::try_join!(
+			PgLocation::create(&connection, "Arizona".into(), Some(usa.clone())),
+			PgLocation::create(&connection, "Utah".into(), Some(usa.clone())),
+		)
+		.unwrap();
+
+		let locations = PgLocation::retrieve_by_ids(&connection, [arizona.id, utah.id, arizona.id])
+			.await
+			.unwrap();
+
+		// The outer locations are retrieved too
+		assert_eq!(locations.len(), 4);
+		assert_eq!(PgLocation::get(&locations, arizona.id).unwrap(), arizona);
+		assert_eq!(PgLocation::get(&locations, utah.id).unwrap(), utah);
+		assert_eq!(PgLocation::get(&locations, usa.id).unwrap(), usa);
+		assert_eq!(PgLocation::get(&locations, earth.id).unwrap(), earth);
+
+		assert!(PgLocation::retrieve_by_ids(&connection, []).await.unwrap().is_empty());
+	}
+}
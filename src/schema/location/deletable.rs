@@ -11,6 +11,10 @@ impl Deletable for PgLocation
 	type Db = Postgres;
 	type Entity = Location;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Location", rows))
+	)]
 	async fn delete<'connection, 'entity, Conn, Iter>(
 		connection: Conn,
 		entities: Iter,
@@ -7,6 +7,7 @@ use super::PgLocation;
 #[async_trait::async_trait]
 impl LocationAdapter for PgLocation
 {
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Location")))]
 	async fn create<'connection, Conn>(
 		connection: Conn,
 		name: String,
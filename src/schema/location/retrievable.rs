@@ -4,12 +4,77 @@ use clinvoice_adapter::{
 	Retrievable,
 };
 use clinvoice_match::MatchLocation;
-use clinvoice_schema::Location;
+use clinvoice_schema::{Id, Location};
 use futures::TryStreamExt;
-use sqlx::{Pool, Postgres, Result, Row};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
 
 use super::PgLocation;
-use crate::fmt::PgLocationRecursiveCte;
+use crate::{
+	fmt::PgLocationRecursiveCte,
+	schema::{
+		count,
+		explain,
+		sort,
+		trace,
+		Countable,
+		Explainable,
+		LocationSortKey,
+		Page,
+		Paginatable,
+		Pagination,
+		RenderedQuery,
+		Sort,
+		Sortable,
+		StrComparable,
+		StrComparison,
+	},
+};
+
+const COLUMNS: LocationColumns<&'static str> = LocationColumns::default();
+
+/// Generate the query which retrieves the [`Id`] of all [`Location`]s that match the
+/// `match_condition`.
+fn query(match_condition: &MatchLocation, comparison: StrComparison) -> QueryBuilder<Postgres>
+{
+	let mut query = PgLocation::query_with_recursive(match_condition, comparison);
+
+	query.push(sql::SELECT).push(COLUMNS.default_scope().id);
+	push_from(&mut query, match_condition);
+
+	query
+}
+
+/// Write the `FROM` clause which selects the [`Location`]s that match the `match_condition` to the
+/// `query`.
+///
+/// The `query` must begin with [`PgLocation::query_with_recursive`].
+fn push_from(query: &mut QueryBuilder<Postgres>, match_condition: &MatchLocation)
+{
+	query.push_from(
+		PgLocationRecursiveCte::from(match_condition),
+		LocationColumns::<char>::DEFAULT_ALIAS,
+	);
+}
+
+/// The (scoped) column which [`Location`]s are sorted by for the `key`.
+fn sort_column(key: LocationSortKey) -> String
+{
+	let columns = COLUMNS.default_scope();
+	match key
+	{
+		LocationSortKey::Id => columns.id.to_string(),
+		LocationSortKey::Name => columns.name.to_string(),
+	}
+}
+
+/// Construct the [`Location`] with the [`Id`] in each of the `rows`.
+async fn rows_to_views(connection: &Pool<Postgres>, rows: &[PgRow]) -> Result<Vec<Location>>
+{
+	trace::rows(rows.len());
+	let ids = rows.iter().map(|row| row.try_get(COLUMNS.id)).collect::<Result<Vec<Id>>>()?;
+	let locations = PgLocation::retrieve_by_ids(connection, ids.iter().copied()).await?;
+	ids.into_iter().map(|id| PgLocation::get(&locations, id)).collect()
+}
 
 /// Implementors of this trait are capable of being retrieved from a [`Database`].
 #[async_trait::async_trait]
@@ -28,22 +93,134 @@ impl Retrievable for PgLocation
 		match_condition: Self::Match,
 	) -> Result<Vec<Self::Entity>>
 	{
-		const COLUMNS: LocationColumns<&'static str> = LocationColumns::default();
-
-		let mut query = Self::query_with_recursive(&match_condition);
-
-		query
-			.push(sql::SELECT)
-			.push(COLUMNS.default_scope().id)
-			.push_from(
-				PgLocationRecursiveCte::from(&match_condition),
-				LocationColumns::<char>::DEFAULT_ALIAS,
-			)
-			.prepare()
-			.fetch(connection)
-			.and_then(|row| Self::retrieve_by_id(connection, row.get(COLUMNS.id)))
-			.try_collect()
-			.await
+		Self::retrieve_comparing(connection, match_condition, StrComparison::Exact).await
+	}
+}
+
+#[async_trait::async_trait]
+impl StrComparable for PgLocation
+{
+	/// Retrieve all [`Location`]s (via `connection`) that match the `match_condition`, comparing
+	/// strings according to the `comparison`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Location", locations, rows))
+	)]
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let rows = query(&match_condition, comparison).prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Paginatable for PgLocation
+{
+	/// Retrieve the [`Page`] of [`Location`]s (via `connection`) that match the `match_condition`
+	/// which is described by the `pagination`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Location", locations, rows))
+	)]
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>
+	{
+		let id = COLUMNS.default_scope().id;
+		let mut query = query(&match_condition, StrComparison::Exact);
+
+		pagination.push_where(&mut query, Default::default(), id, None)?;
+		pagination.push_order_limit(&mut query, id, None)?;
+
+		let mut rows = query.prepare().fetch_all(connection).await?;
+		let next = pagination.next(&mut rows, COLUMNS.id, None)?;
+
+		Ok(Page { entities: rows_to_views(connection, &rows).await?, next })
+	}
+}
+
+#[async_trait::async_trait]
+impl Sortable for PgLocation
+{
+	type SortKey = LocationSortKey;
+
+	/// Retrieve all [`Location`]s (via `connection`) that match the `match_condition`, ordered by
+	/// the `sort`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Location", locations, rows))
+	)]
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>
+	{
+		let mut query = query(&match_condition, StrComparison::Exact);
+		sort::push_order_by(&mut query, sort, sort_column, COLUMNS.default_scope().id);
+
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Countable for PgLocation
+{
+	/// Count the [`Location`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Location")))]
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>
+	{
+		let mut query = Self::query_with_recursive(&match_condition, StrComparison::Exact);
+
+		query.push(sql::SELECT).push(count::COUNT);
+		push_from(&mut query, &match_condition);
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+
+	/// Check whether there are any [`Location`]s (via `connection`) that match the
+	/// `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Location")))]
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>
+	{
+		let mut query = Self::query_with_recursive(&match_condition, StrComparison::Exact);
+
+		query.push(sql::SELECT).push(count::EXISTS);
+		push_from(&mut query, &match_condition);
+		query.push(')');
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+}
+
+#[async_trait::async_trait]
+impl Explainable for PgLocation
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the query which retrieves the
+	/// [`Location`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Location")))]
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>
+	{
+		let mut query = query(&match_condition, StrComparison::Exact);
+		explain::explain(connection, &mut query).await
+	}
+
+	/// Render the query which retrieves the [`Location`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Location")))]
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>
+	{
+		let mut query = query(&match_condition, StrComparison::Exact);
+		explain::render(connection, &mut query).await
 	}
 }
 
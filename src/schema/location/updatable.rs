@@ -13,6 +13,10 @@ impl Updatable for PgLocation
 	type Db = Postgres;
 	type Entity = Location;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Location", rows))
+	)]
 	async fn update<'entity, Iter>(
 		connection: &mut Transaction<Self::Db>,
 		entities: Iter,
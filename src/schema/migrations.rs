@@ -0,0 +1,197 @@
+use sqlx::{migrate::MigrateError, Acquire, Executor, Postgres, Result};
+
+/// The key used with `pg_advisory_xact_lock` to ensure that only one connection is migrating the
+/// database at any given time.
+const LOCK_KEY: i64 = 0x636c_696e_766f_6963;
+
+/// Declare a [`Migration`] whose `up` and `down` SQL is stored in the `migrations` directory as
+/// `{name}.up.sql` and `{name}.down.sql` respectively.
+macro_rules! migration {
+	($version:literal, $name:literal, $description:literal) => {
+		Migration {
+			description: $description,
+			down: include_str!(concat!("migrations/", $name, ".down.sql")),
+			up: include_str!(concat!("migrations/", $name, ".up.sql")),
+			version: $version,
+		}
+	};
+}
+
+/// Every [`Migration`] known to this crate, in the order they must be applied.
+pub(super) const MIGRATIONS: &[Migration] = &[
+	migration!(1, "0001_initial", "Create the initial tables"),
+	migration!(2, "0002_money_currency", "Store the currency of money alongside its amount"),
+	migration!(3, "0003_exchange_rates", "Create the exchange rates table"),
+	migration!(4, "0004_exchange_rates_by_date", "Key exchange rates by the date they apply to"),
+	migration!(5, "0005_money_numeric", "Store amounts of money as numeric instead of text"),
+	migration!(6, "0006_indexes", "Index foreign keys and commonly filtered columns"),
+	migration!(7, "0007_full_text_search", "Add full-text search vectors to jobs and timesheets"),
+	migration!(8, "0008_billable_duration", "Add functions which round time up to an increment"),
+	migration!(9, "0009_report_views", "Add materialized views for reports"),
+	migration!(10, "0010_timesheet_charges", "Add a view of what is charged for each timesheet"),
+];
+
+/// A single, reversible step in the evolution of the database schema.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub(super) struct Migration
+{
+	/// A summary of what the migration does, which is recorded in the `schema_migrations` table.
+	pub(super) description: &'static str,
+
+	/// The SQL which reverts everything that `up` did.
+	pub(super) down: &'static str,
+
+	/// The SQL which applies the migration.
+	pub(super) up: &'static str,
+
+	/// The version of the schema after this migration has been applied.
+	pub(super) version: i64,
+}
+
+/// Initialize the `schema_migrations` table.
+async fn init_schema_migrations<'connection, Conn>(connection: Conn) -> Result<()>
+where
+	Conn: Executor<'connection, Database = Postgres>,
+{
+	connection
+		.execute(
+			"CREATE TABLE IF NOT EXISTS schema_migrations
+			(
+				version bigint PRIMARY KEY,
+				description text NOT NULL,
+				applied_at timestamptz NOT NULL DEFAULT now()
+			);",
+		)
+		.await?;
+	Ok(())
+}
+
+/// The version of the most recent [`Migration`] in [`MIGRATIONS`].
+pub(super) fn latest_version() -> i64
+{
+	MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Retrieve the version of the most recent [`Migration`] applied to the database, or [`None`] if
+/// the database has never been migrated.
+pub(super) async fn current_version<'connection, Conn>(connection: Conn) -> Result<Option<i64>>
+where
+	Conn: Acquire<'connection, Database = Postgres>,
+{
+	let mut connection = connection.acquire().await?;
+
+	// NOTE: these are not `query!` macros because `schema_migrations` may not exist yet.
+	let is_migrated: bool =
+		sqlx::query_scalar("SELECT to_regclass('schema_migrations') IS NOT NULL;")
+			.fetch_one(&mut *connection)
+			.await?;
+
+	if !is_migrated
+	{
+		return Ok(None);
+	}
+
+	sqlx::query_scalar("SELECT max(version) FROM schema_migrations;")
+		.fetch_one(&mut *connection)
+		.await
+}
+
+/// Apply (or revert) [`MIGRATIONS`] until the database is at the given `version`.
+///
+/// A `version` of `0` reverts every [`Migration`].
+///
+/// # Errors
+///
+/// * [`MigrateError::VersionMissing`] if `version` is not the version of any [`Migration`].
+/// * [`MigrateError::VersionMissing`] if the database has been migrated by a newer version of this
+///   crate.
+/// * If any of the [`Migration`]s fail, in which case the database is left untouched.
+pub(super) async fn migrate_to<'connection, Conn>(connection: Conn, version: i64) -> Result<()>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	if version != 0 && !MIGRATIONS.iter().any(|m| m.version == version)
+	{
+		return Err(MigrateError::VersionMissing(version).into());
+	}
+
+	let mut transaction = connection.begin().await?;
+
+	sqlx::query("SELECT pg_advisory_xact_lock($1);")
+		.bind(LOCK_KEY)
+		.execute(&mut transaction)
+		.await?;
+
+	init_schema_migrations(&mut transaction).await?;
+
+	let current: i64 =
+		sqlx::query_scalar::<_, Option<_>>("SELECT max(version) FROM schema_migrations;")
+			.fetch_one(&mut transaction)
+			.await?
+			.unwrap_or(0);
+
+	if current > latest_version()
+	{
+		return Err(MigrateError::VersionMissing(current).into());
+	}
+
+	if current < version
+	{
+		for migration in MIGRATIONS.iter().filter(|m| current < m.version && m.version <= version)
+		{
+			(&mut transaction).execute(migration.up).await?;
+			sqlx::query("INSERT INTO schema_migrations (version, description) VALUES ($1, $2);")
+				.bind(migration.version)
+				.bind(migration.description)
+				.execute(&mut transaction)
+				.await?;
+		}
+	}
+	else
+	{
+		for migration in
+			MIGRATIONS.iter().rev().filter(|m| version < m.version && m.version <= current)
+		{
+			(&mut transaction).execute(migration.down).await?;
+			sqlx::query("DELETE FROM schema_migrations WHERE version = $1;")
+				.bind(migration.version)
+				.execute(&mut transaction)
+				.await?;
+		}
+	}
+
+	transaction.commit().await
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+
+	use super::MIGRATIONS;
+	use crate::{schema::util, PgSchema};
+
+	#[test]
+	fn versions_ascend()
+	{
+		assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+		assert!(MIGRATIONS.iter().all(|m| m.version > 0));
+	}
+
+	#[tokio::test]
+	async fn migrate()
+	{
+		let connection = util::connect().await;
+
+		// Migrating more than once must be harmless
+		PgSchema::migrate(&connection).await.unwrap();
+		PgSchema::migrate(&connection).await.unwrap();
+
+		assert_eq!(
+			PgSchema::current_version(&connection).await.unwrap(),
+			Some(super::latest_version()),
+		);
+
+		assert!(PgSchema::migrate_to(&connection, super::latest_version() + 1).await.is_err());
+	}
+}
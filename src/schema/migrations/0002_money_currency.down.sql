@@ -0,0 +1,5 @@
+-- NOTE: amounts are left in their original currency, as there is no way to exchange them here.
+ALTER TABLE expenses DROP COLUMN cost_currency;
+ALTER TABLE jobs DROP COLUMN invoice_hourly_rate_currency;
+
+DROP DOMAIN currency_code;
@@ -0,0 +1,8 @@
+CREATE DOMAIN currency_code AS text CHECK (VALUE ~ '^[A-Z]{3}$');
+
+-- NOTE: previous versions exchanged all money into `Currency::default()` before storing it.
+ALTER TABLE jobs ADD COLUMN invoice_hourly_rate_currency currency_code NOT NULL DEFAULT 'EUR';
+ALTER TABLE jobs ALTER COLUMN invoice_hourly_rate_currency DROP DEFAULT;
+
+ALTER TABLE expenses ADD COLUMN cost_currency currency_code NOT NULL DEFAULT 'EUR';
+ALTER TABLE expenses ALTER COLUMN cost_currency DROP DEFAULT;
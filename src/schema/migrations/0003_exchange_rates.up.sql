@@ -0,0 +1,7 @@
+CREATE TABLE exchange_rates
+(
+	currency currency_code PRIMARY KEY,
+	rate numeric NOT NULL,
+
+	CONSTRAINT exchange_rates__rate_is_positive CHECK (rate > 0)
+);
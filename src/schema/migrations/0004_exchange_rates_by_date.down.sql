@@ -0,0 +1,10 @@
+DROP FUNCTION exchange(numeric, currency_code, currency_code, date);
+DROP FUNCTION exchange_rate(currency_code, date);
+
+-- Only the most recent rate of each currency can be kept.
+DELETE FROM exchange_rates R
+	WHERE EXISTS (SELECT 1 FROM exchange_rates R2 WHERE R2.currency = R.currency AND R2.date > R.date);
+
+ALTER TABLE exchange_rates DROP CONSTRAINT exchange_rates_pkey;
+ALTER TABLE exchange_rates DROP COLUMN date;
+ALTER TABLE exchange_rates ADD CONSTRAINT exchange_rates_pkey PRIMARY KEY (currency);
@@ -0,0 +1,24 @@
+ALTER TABLE exchange_rates ADD COLUMN date date NOT NULL DEFAULT current_date;
+ALTER TABLE exchange_rates ALTER COLUMN date DROP DEFAULT;
+ALTER TABLE exchange_rates DROP CONSTRAINT exchange_rates_pkey;
+ALTER TABLE exchange_rates ADD CONSTRAINT exchange_rates_pkey PRIMARY KEY (currency, date);
+
+-- The rate of the `currency` (relative to 'EUR') which was effective on the given date: that is,
+-- the most recent rate on or before it.
+CREATE FUNCTION exchange_rate(currency_code, date) RETURNS numeric
+	LANGUAGE sql STABLE
+	AS $$
+		SELECT CASE WHEN $1 = 'EUR' THEN 1 ELSE (
+			SELECT R.rate FROM exchange_rates R
+			WHERE R.currency = $1 AND R.date <= $2
+			ORDER BY R.date DESC
+			LIMIT 1
+		) END;
+	$$;
+
+-- Exchange an amount from one currency to another, using the rates effective on the given date.
+CREATE FUNCTION exchange(numeric, currency_code, currency_code, date) RETURNS numeric
+	LANGUAGE sql STABLE
+	AS $$
+		SELECT CASE WHEN $2 = $3 THEN $1 ELSE $1 / exchange_rate($2, $4) * exchange_rate($3, $4) END;
+	$$;
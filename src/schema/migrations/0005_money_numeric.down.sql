@@ -0,0 +1,12 @@
+DROP INDEX expenses__cost_idx;
+DROP INDEX jobs__invoice_hourly_rate_idx;
+
+CREATE DOMAIN amount_of_currency AS text CHECK (VALUE ~ '^\d+(\.\d+)?$');
+
+ALTER TABLE expenses
+	DROP CONSTRAINT expenses__cost_not_negative,
+	ALTER COLUMN cost TYPE amount_of_currency USING cost::text;
+
+ALTER TABLE jobs
+	DROP CONSTRAINT jobs__invoice_hourly_rate_not_negative,
+	ALTER COLUMN invoice_hourly_rate TYPE amount_of_currency USING invoice_hourly_rate::text;
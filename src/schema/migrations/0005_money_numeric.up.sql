@@ -0,0 +1,13 @@
+ALTER TABLE jobs
+	ALTER COLUMN invoice_hourly_rate TYPE numeric USING invoice_hourly_rate::numeric,
+	ADD CONSTRAINT jobs__invoice_hourly_rate_not_negative CHECK (invoice_hourly_rate >= 0);
+
+ALTER TABLE expenses
+	ALTER COLUMN cost TYPE numeric USING cost::numeric,
+	ADD CONSTRAINT expenses__cost_not_negative CHECK (cost >= 0);
+
+DROP DOMAIN amount_of_currency;
+
+-- NOTE: amounts are only ever compared to amounts of the same currency.
+CREATE INDEX jobs__invoice_hourly_rate_idx ON jobs (invoice_hourly_rate_currency, invoice_hourly_rate);
+CREATE INDEX expenses__cost_idx ON expenses (cost_currency, cost);
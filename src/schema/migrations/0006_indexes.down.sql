@@ -0,0 +1,10 @@
+DROP INDEX timesheets__time_begin_idx;
+DROP INDEX jobs__invoice_date_issued_idx;
+DROP INDEX jobs__date_open_idx;
+
+DROP INDEX timesheets__job_id_idx;
+DROP INDEX organizations__location_id_idx;
+DROP INDEX locations__outer_id_idx;
+DROP INDEX jobs__client_id_idx;
+DROP INDEX expenses__timesheet_id_idx;
+DROP INDEX contact_information__address_id_idx;
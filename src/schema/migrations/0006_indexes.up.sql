@@ -0,0 +1,14 @@
+-- NOTE: foreign keys are indexed so that joining on them (and the recursive location CTE) does not
+--       require a scan of the referencing table. `timesheets.employee_id` is already covered by
+--       `timesheets__employee_job_time_uq`.
+CREATE INDEX contact_information__address_id_idx ON contact_information (address_id);
+CREATE INDEX expenses__timesheet_id_idx ON expenses (timesheet_id);
+CREATE INDEX jobs__client_id_idx ON jobs (client_id);
+CREATE INDEX locations__outer_id_idx ON locations (outer_id);
+CREATE INDEX organizations__location_id_idx ON organizations (location_id);
+CREATE INDEX timesheets__job_id_idx ON timesheets (job_id);
+
+-- NOTE: these columns are commonly filtered and sorted by.
+CREATE INDEX jobs__date_open_idx ON jobs (date_open);
+CREATE INDEX jobs__invoice_date_issued_idx ON jobs (invoice_date_issued);
+CREATE INDEX timesheets__time_begin_idx ON timesheets (time_begin);
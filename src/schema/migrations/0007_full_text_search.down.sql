@@ -0,0 +1,5 @@
+DROP INDEX timesheets__search_vector_idx;
+DROP INDEX jobs__search_vector_idx;
+
+ALTER TABLE timesheets DROP COLUMN search_vector;
+ALTER TABLE jobs DROP COLUMN search_vector;
@@ -0,0 +1,14 @@
+-- NOTE: the text search configuration must be given explicitly for the expression to be immutable.
+ALTER TABLE jobs ADD COLUMN search_vector tsvector NOT NULL GENERATED ALWAYS AS
+(
+	setweight(to_tsvector('english', objectives), 'A') ||
+	setweight(to_tsvector('english', notes), 'B')
+) STORED;
+
+ALTER TABLE timesheets ADD COLUMN search_vector tsvector NOT NULL GENERATED ALWAYS AS
+(
+	to_tsvector('english', work_notes)
+) STORED;
+
+CREATE INDEX jobs__search_vector_idx ON jobs USING gin (search_vector);
+CREATE INDEX timesheets__search_vector_idx ON timesheets USING gin (search_vector);
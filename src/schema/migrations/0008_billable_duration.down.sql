@@ -0,0 +1,2 @@
+DROP FUNCTION to_hours(interval);
+DROP FUNCTION billable_duration(timestamptz, timestamptz, interval);
@@ -0,0 +1,17 @@
+-- The time between `time_begin` and `time_end`, rounded up to the nearest multiple of `increment`
+-- (e.g. 20 minutes in increments of 15 minutes is 30 minutes). An `increment` which is not
+-- positive does not round at all.
+CREATE FUNCTION billable_duration(timestamptz, timestamptz, interval) RETURNS interval
+	LANGUAGE sql IMMUTABLE STRICT
+	AS $$
+		SELECT CASE WHEN $3 <= interval '0' THEN $2 - $1 ELSE
+			ceil(extract(epoch FROM $2 - $1) / extract(epoch FROM $3))::float8 * $3
+		END;
+	$$;
+
+-- The number of hours in an `interval`.
+CREATE FUNCTION to_hours(interval) RETURNS numeric
+	LANGUAGE sql IMMUTABLE STRICT
+	AS $$
+		SELECT extract(epoch FROM $1)::numeric / 3600;
+	$$;
@@ -0,0 +1,2 @@
+DROP MATERIALIZED VIEW report_client_revenue_by_month;
+DROP MATERIALIZED VIEW report_job_hours_by_day;
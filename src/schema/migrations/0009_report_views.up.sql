@@ -0,0 +1,54 @@
+-- NOTE: these views are only as fresh as the last `PgSchema::refresh_reports`. Each has a unique
+--       index so that it can be refreshed concurrently, without blocking readers.
+
+-- The hours logged for each job on each day (in UTC). Timesheets which have not ended yet are not
+-- included.
+CREATE MATERIALIZED VIEW report_job_hours_by_day AS
+	SELECT T.job_id, date_trunc('day', T.time_begin, 'UTC') AS day,
+		count(*) AS timesheets,
+		to_hours(sum(T.time_end - T.time_begin)) AS hours,
+		to_hours(sum(billable_duration(T.time_begin, T.time_end, J.increment))) AS billable_hours
+	FROM timesheets T
+	JOIN jobs J ON (J.id = T.job_id)
+	WHERE T.time_end IS NOT NULL
+	GROUP BY T.job_id, date_trunc('day', T.time_begin, 'UTC');
+
+CREATE UNIQUE INDEX report_job_hours_by_day__job_day_uq
+	ON report_job_hours_by_day (job_id, day);
+
+-- The revenue from each client in each month (in UTC) that it was invoiced, in the currency of the
+-- invoice. The `expenses` (and `total`) are null if any expense could not be exchanged, because
+-- `exchange_rates` has no rate for its currency on the date the invoice was issued.
+CREATE MATERIALIZED VIEW report_client_revenue_by_month AS
+	SELECT J.client_id AS organization_id, J.invoice_hourly_rate_currency AS currency,
+		date_trunc('month', J.invoice_date_issued, 'UTC') AS month,
+		sum(R.labor) AS labor,
+		CASE WHEN count(*) = count(R.expenses) THEN sum(R.expenses) END AS expenses,
+		sum(R.labor) + CASE WHEN count(*) = count(R.expenses) THEN sum(R.expenses) END AS total
+	FROM jobs J
+	JOIN timesheets T ON (T.job_id = J.id AND T.time_end IS NOT NULL)
+	CROSS JOIN LATERAL
+	(
+		SELECT J.invoice_hourly_rate *
+				to_hours(billable_duration(T.time_begin, T.time_end, J.increment)) AS labor,
+			(
+				SELECT CASE WHEN count(*) = count(E.amount) THEN coalesce(sum(E.amount), 0) END
+				FROM
+				(
+					SELECT exchange(
+						X.cost,
+						X.cost_currency,
+						J.invoice_hourly_rate_currency,
+						(J.invoice_date_issued AT TIME ZONE 'UTC')::date
+					) AS amount
+					FROM expenses X
+					WHERE X.timesheet_id = T.id
+				) E
+			) AS expenses
+	) R
+	WHERE J.invoice_date_issued IS NOT NULL
+	GROUP BY J.client_id, J.invoice_hourly_rate_currency,
+		date_trunc('month', J.invoice_date_issued, 'UTC');
+
+CREATE UNIQUE INDEX report_client_revenue_by_month__client_month_uq
+	ON report_client_revenue_by_month (organization_id, month, currency);
@@ -0,0 +1,40 @@
+DROP MATERIALIZED VIEW report_client_revenue_by_month;
+
+-- The revenue from each client in each month (in UTC) that it was invoiced, in the currency of the
+-- invoice. The `expenses` (and `total`) are null if any expense could not be exchanged, because
+-- `exchange_rates` has no rate for its currency on the date the invoice was issued.
+CREATE MATERIALIZED VIEW report_client_revenue_by_month AS
+	SELECT J.client_id AS organization_id, J.invoice_hourly_rate_currency AS currency,
+		date_trunc('month', J.invoice_date_issued, 'UTC') AS month,
+		sum(R.labor) AS labor,
+		CASE WHEN count(*) = count(R.expenses) THEN sum(R.expenses) END AS expenses,
+		sum(R.labor) + CASE WHEN count(*) = count(R.expenses) THEN sum(R.expenses) END AS total
+	FROM jobs J
+	JOIN timesheets T ON (T.job_id = J.id AND T.time_end IS NOT NULL)
+	CROSS JOIN LATERAL
+	(
+		SELECT J.invoice_hourly_rate *
+				to_hours(billable_duration(T.time_begin, T.time_end, J.increment)) AS labor,
+			(
+				SELECT CASE WHEN count(*) = count(E.amount) THEN coalesce(sum(E.amount), 0) END
+				FROM
+				(
+					SELECT exchange(
+						X.cost,
+						X.cost_currency,
+						J.invoice_hourly_rate_currency,
+						(J.invoice_date_issued AT TIME ZONE 'UTC')::date
+					) AS amount
+					FROM expenses X
+					WHERE X.timesheet_id = T.id
+				) E
+			) AS expenses
+	) R
+	WHERE J.invoice_date_issued IS NOT NULL
+	GROUP BY J.client_id, J.invoice_hourly_rate_currency,
+		date_trunc('month', J.invoice_date_issued, 'UTC');
+
+CREATE UNIQUE INDEX report_client_revenue_by_month__client_month_uq
+	ON report_client_revenue_by_month (organization_id, month, currency);
+
+DROP VIEW timesheet_charges;
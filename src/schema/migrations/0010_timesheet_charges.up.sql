@@ -0,0 +1,49 @@
+-- What is charged for each timesheet which has ended, in the currency of the invoice of its job.
+-- Every expense is exchanged using the rates effective on the day (in UTC) that its timesheet
+-- began. The `expenses` are null if any expense could not be exchanged, because `exchange_rates`
+-- has no rate for its currency on that date.
+CREATE VIEW timesheet_charges AS
+	SELECT T.id AS timesheet_id, T.job_id, J.invoice_hourly_rate_currency AS currency,
+		H.billable_hours,
+		J.invoice_hourly_rate * H.billable_hours AS labor,
+		(
+			SELECT CASE WHEN count(*) = count(E.amount) THEN coalesce(sum(E.amount), 0) END
+			FROM
+			(
+				SELECT exchange(
+					X.cost,
+					X.cost_currency,
+					J.invoice_hourly_rate_currency,
+					(T.time_begin AT TIME ZONE 'UTC')::date
+				) AS amount
+				FROM expenses X
+				WHERE X.timesheet_id = T.id
+			) E
+		) AS expenses
+	FROM timesheets T
+	JOIN jobs J ON (J.id = T.job_id)
+	CROSS JOIN LATERAL
+	(
+		SELECT to_hours(billable_duration(T.time_begin, T.time_end, J.increment)) AS billable_hours
+	) H
+	WHERE T.time_end IS NOT NULL;
+
+-- NOTE: the report is recreated from `timesheet_charges`, so that it always agrees with
+--       `PgJob::revenue`.
+DROP MATERIALIZED VIEW report_client_revenue_by_month;
+
+-- The revenue from each client in each month (in UTC) that it was invoiced, in the currency of the
+-- invoice. The `expenses` (and `total`) are null if any expense could not be exchanged.
+CREATE MATERIALIZED VIEW report_client_revenue_by_month AS
+	SELECT J.client_id AS organization_id, C.currency,
+		date_trunc('month', J.invoice_date_issued, 'UTC') AS month,
+		sum(C.labor) AS labor,
+		CASE WHEN count(*) = count(C.expenses) THEN sum(C.expenses) END AS expenses,
+		sum(C.labor) + CASE WHEN count(*) = count(C.expenses) THEN sum(C.expenses) END AS total
+	FROM jobs J
+	JOIN timesheet_charges C ON (C.job_id = J.id)
+	WHERE J.invoice_date_issued IS NOT NULL
+	GROUP BY J.client_id, C.currency, date_trunc('month', J.invoice_date_issued, 'UTC');
+
+CREATE UNIQUE INDEX report_client_revenue_by_month__client_month_uq
+	ON report_client_revenue_by_month (organization_id, month, currency);
@@ -1,13 +1,18 @@
+mod aged_receivables;
 mod deletable;
 mod organization_adapter;
 mod retrievable;
 mod updatable;
 
-use clinvoice_adapter::schema::columns::OrganizationColumns;
-use clinvoice_schema::Organization;
-use sqlx::{postgres::PgRow, Executor, Postgres, Result, Row};
+use std::collections::HashMap;
 
-use super::PgLocation;
+pub use aged_receivables::AgedReceivables;
+use clinvoice_adapter::{fmt::sql, schema::columns::OrganizationColumns};
+use clinvoice_match::MatchOrganization;
+use clinvoice_schema::{Id, Location, Organization};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
+
+use super::{PgLocation, StrComparison};
 
 /// Implementor of the [`OrganizationAdapter`](clinvoice_adapter::schema::OrganizationAdapter) for
 /// the [`Postgres`](sqlx::Postgres) database.
@@ -15,20 +20,57 @@ pub struct PgOrganization;
 
 impl PgOrganization
 {
-	pub(super) async fn row_to_view<'connection, Conn, Column>(
-		connection: Conn,
+	/// Retrieve the [`AgedReceivables`] of every client [`Organization`] that matches the
+	/// `match_condition`: what is owed for each [`Job`](clinvoice_schema::Job) whose
+	/// [`Invoice`](clinvoice_schema::Invoice) has been issued but not paid, grouped by how long ago
+	/// it was issued.
+	///
+	/// Each [`Organization`] has one [`AgedReceivables`] for every currency it has been invoiced
+	/// in, sorted by [`Id`] and then currency. The total of each
+	/// [`Invoice`](clinvoice_schema::Invoice) is computed by the database the same way as
+	/// [`PgJob::invoice_summary`](super::PgJob::invoice_summary), which also describes the errors
+	/// that may occur.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Organization", rows))
+	)]
+	pub async fn aged_receivables(
+		connection: &Pool<Postgres>,
+		match_condition: MatchOrganization,
+	) -> Result<Vec<AgedReceivables>>
+	{
+		aged_receivables::retrieve(connection, &match_condition).await
+	}
+
+	/// Write `(SELECT … FROM … WHERE …)`, which selects the [`Id`] of every [`Organization`] that
+	/// matches the `match_condition`, to the `query`.
+	///
+	/// The `query` must begin with the [`PgLocation::query_with_recursive`] of the
+	/// `match_condition.location`.
+	pub(super) fn push_matching_ids(
+		query: &mut QueryBuilder<Postgres>,
+		match_condition: &MatchOrganization,
+	)
+	{
+		query.push('(').push(sql::SELECT).push(OrganizationColumns::default().default_scope().id);
+		retrievable::push_from_where(query, match_condition, StrComparison::Exact);
+		query.push(')');
+	}
+
+	/// Construct an [`Organization`] from the `row`, whose [`Location`] must be among the
+	/// `locations` (e.g. from [`PgLocation::retrieve_for_rows`]).
+	pub(super) fn row_to_view<Column>(
 		columns: OrganizationColumns<Column>,
+		locations: &HashMap<Id, Location>,
 		row: &PgRow,
 	) -> Result<Organization>
 	where
-		Conn: Executor<'connection, Database = Postgres>,
 		Column: AsRef<str>,
 	{
-		let location_id = row.try_get(columns.location_id.as_ref())?;
 		Ok(Organization {
 			id: row.try_get(columns.id.as_ref())?,
 			name: row.try_get(columns.name.as_ref())?,
-			location: PgLocation::retrieve_by_id(connection, location_id).await?,
+			location: PgLocation::get(locations, row.try_get(columns.location_id.as_ref())?)?,
 		})
 	}
 }
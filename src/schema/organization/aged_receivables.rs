@@ -0,0 +1,270 @@
+use clinvoice_adapter::fmt::QueryBuilderExt;
+use clinvoice_match::MatchOrganization;
+use clinvoice_schema::Id;
+use money2::Money;
+use sqlx::{postgres::PgRow, Pool, Postgres, Result, Row};
+
+use super::PgOrganization;
+use crate::schema::{trace, util, PgLocation, StrComparison};
+
+/// What a client [`Organization`](clinvoice_schema::Organization) owes for the
+/// [`Invoice`](clinvoice_schema::Invoice)s which have been issued but not paid, grouped by how many
+/// days ago they were issued.
+///
+/// Every amount is in the same currency, since [`Invoice`](clinvoice_schema::Invoice)s in
+/// different currencies are not added together.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AgedReceivables
+{
+	/// The amount owed for [`Invoice`](clinvoice_schema::Invoice)s issued 30 or fewer days ago.
+	pub days_0_to_30: Money,
+
+	/// The amount owed for [`Invoice`](clinvoice_schema::Invoice)s issued 31 to 60 days ago.
+	pub days_31_to_60: Money,
+
+	/// The amount owed for [`Invoice`](clinvoice_schema::Invoice)s issued 61 to 90 days ago.
+	pub days_61_to_90: Money,
+
+	/// The amount owed for [`Invoice`](clinvoice_schema::Invoice)s issued more than 90 days ago.
+	pub days_over_90: Money,
+
+	/// The number of unpaid [`Invoice`](clinvoice_schema::Invoice)s.
+	pub invoices: i64,
+
+	/// The [`Id`] of the client [`Organization`](clinvoice_schema::Organization).
+	pub organization_id: Id,
+
+	/// The amount owed for every unpaid [`Invoice`](clinvoice_schema::Invoice).
+	pub total: Money,
+}
+
+impl AgedReceivables
+{
+	/// Construct [`AgedReceivables`] from the `row`.
+	fn from_row(row: &PgRow) -> Result<Self>
+	{
+		let currency: String = row.try_get("currency")?;
+		Ok(Self {
+			days_0_to_30: util::money_from(row.try_get("days_0_to_30")?, &currency)?,
+			days_31_to_60: util::money_from(row.try_get("days_31_to_60")?, &currency)?,
+			days_61_to_90: util::money_from(row.try_get("days_61_to_90")?, &currency)?,
+			days_over_90: util::money_from(row.try_get("days_over_90")?, &currency)?,
+			invoices: row.try_get("invoices")?,
+			organization_id: row.try_get("organization_id")?,
+			total: util::money_from(row.try_get("total")?, &currency)?,
+		})
+	}
+}
+
+/// Retrieve the [`AgedReceivables`] of each client [`Organization`](clinvoice_schema::Organization)
+/// which matches the `match_condition`, and each currency it has been invoiced in.
+///
+/// The total of each [`Invoice`](clinvoice_schema::Invoice) is computed the same way as
+/// [`PgJob::invoice_summary`](crate::schema::PgJob::invoice_summary).
+pub(super) async fn retrieve(
+	connection: &Pool<Postgres>,
+	match_condition: &MatchOrganization,
+) -> Result<Vec<AgedReceivables>>
+{
+	let mut query =
+		PgLocation::query_with_recursive(&match_condition.location, StrComparison::Exact);
+
+	// NOTE: if any expense of an invoice could not be exchanged, the `total` is `NULL` so that
+	//       decoding it fails instead of silently leaving the invoice out of the sum.
+	query.push(
+		" SELECT J.client_id AS organization_id, J.invoice_hourly_rate_currency AS currency,
+			count(*) AS invoices,
+			coalesce(sum(I.total) FILTER (WHERE I.age <= 30), 0) AS days_0_to_30,
+			coalesce(sum(I.total) FILTER (WHERE I.age BETWEEN 31 AND 60), 0) AS days_31_to_60,
+			coalesce(sum(I.total) FILTER (WHERE I.age BETWEEN 61 AND 90), 0) AS days_61_to_90,
+			coalesce(sum(I.total) FILTER (WHERE I.age > 90), 0) AS days_over_90,
+			CASE WHEN count(*) = count(I.total) THEN sum(I.total) END AS total
+		FROM jobs J
+		CROSS JOIN LATERAL
+		(
+			SELECT
+				(now() AT TIME ZONE 'UTC')::date -
+					(J.invoice_date_issued AT TIME ZONE 'UTC')::date AS age,
+				(
+					SELECT CASE WHEN count(*) = count(C.expenses)
+						THEN coalesce(sum(C.labor + C.expenses), 0)
+					END
+					FROM timesheet_charges C
+					WHERE C.job_id = J.id
+				) AS total
+		) I
+		WHERE J.invoice_date_issued IS NOT NULL AND J.invoice_date_paid IS NULL
+			AND J.client_id IN ",
+	);
+
+	PgOrganization::push_matching_ids(&mut query, match_condition);
+
+	query.push(
+		" GROUP BY J.client_id, J.invoice_hourly_rate_currency
+		ORDER BY J.client_id, J.invoice_hourly_rate_currency;",
+	);
+
+	let rows = query.prepare().fetch_all(connection).await?;
+	trace::rows(rows.len());
+	rows.iter().map(AgedReceivables::from_row).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	};
+	use clinvoice_match::{Match, MatchLocation, MatchOrganization, MatchOuterLocation};
+	use clinvoice_schema::{chrono, Invoice, InvoiceDate};
+	use money2::{Currency, Money};
+	use pretty_assertions::assert_eq;
+
+	use super::AgedReceivables;
+	use crate::{
+		schema::{util, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn retrieve()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		let (earth, mars) = futures::try_join!(
+			PgLocation::create(&connection, "Earth".into(), None),
+			PgLocation::create(&connection, "Mars".into(), None),
+		)
+		.unwrap();
+
+		let usa = PgLocation::create(&connection, "USA".into(), Some(earth)).await.unwrap();
+
+		let (organization, organization2) = futures::try_join!(
+			PgOrganization::create(&connection, usa, "Some Organization".into()),
+			PgOrganization::create(&connection, mars, "Some Other Organizatión".into()),
+		)
+		.unwrap();
+
+		let employee =
+			PgEmployee::create(&connection, "My Name".into(), "Employed".into(), "Janitor".into())
+				.await
+				.unwrap();
+
+		let now = chrono::Utc::now();
+		let invoice = |days_ago, paid, hourly_rate| Invoice {
+			date: Some(InvoiceDate {
+				issued: now - chrono::Duration::days(days_ago),
+				paid:   paid.then_some(now),
+			}),
+			hourly_rate,
+		};
+
+		let usd = Money::new(20_00, 2, Currency::Usd);
+		let jobs = futures::future::try_join_all(
+			[
+				(&organization, Some(invoice(10, false, usd))),
+				(&organization, Some(invoice(45, false, usd))),
+				(&organization, Some(invoice(100, false, usd))),
+				(&organization, Some(invoice(75, true, usd))),
+				(&organization, None),
+				(&organization, Some(invoice(5, false, Money::new(10_00, 2, Currency::Eur)))),
+				(&organization2, Some(invoice(10, false, usd))),
+			]
+			.into_iter()
+			.map(|(client, invoice)| {
+				PgJob::create(
+					&connection,
+					client.clone(),
+					None,
+					now - chrono::Duration::days(120),
+					Duration::from_secs(900),
+					invoice.unwrap_or(Invoice { date: None, hourly_rate: usd }),
+					String::new(),
+					"Get paid".into(),
+				)
+			}),
+		)
+		.await
+		.unwrap();
+
+		// {{{
+		let mut transaction = connection.begin().await.unwrap();
+
+		for (job, minutes, expenses) in [
+			(&jobs[0], 60, vec![(
+				"Travel".into(),
+				Money::new(5_00, 2, Currency::Usd),
+				"Bus".into(),
+			)]),
+			(&jobs[1], 60, Vec::new()),
+			(&jobs[2], 6, Vec::new()),
+			(&jobs[3], 60, Vec::new()),
+			(&jobs[4], 60, Vec::new()),
+			(&jobs[5], 60, Vec::new()),
+			(&jobs[6], 60, Vec::new()),
+		]
+		{
+			let time_begin = job.date_open;
+			PgTimesheet::create(
+				&mut transaction,
+				employee.clone(),
+				expenses,
+				job.clone(),
+				time_begin,
+				Some(time_begin + chrono::Duration::minutes(minutes)),
+				String::new(),
+			)
+			.await
+			.unwrap();
+		}
+
+		transaction.commit().await.unwrap();
+		// }}}
+
+		let usd = |amount| Money::new(amount, 2, Currency::Usd);
+		let eur = |amount| Money::new(amount, 2, Currency::Eur);
+
+		assert_eq!(
+			PgOrganization::aged_receivables(&connection, MatchOrganization {
+				id: Match::Or(vec![organization.id.into(), organization2.id.into()]),
+				location: MatchLocation {
+					outer: MatchOuterLocation::Some(Box::new(MatchLocation {
+						name: "Earth".to_owned().into(),
+						..Default::default()
+					})),
+					..Default::default()
+				},
+				..Default::default()
+			})
+			.await
+			.unwrap(),
+			[
+				AgedReceivables {
+					days_0_to_30: eur(10_00),
+					days_31_to_60: eur(0),
+					days_61_to_90: eur(0),
+					days_over_90: eur(0),
+					invoices: 1,
+					organization_id: organization.id,
+					total: eur(10_00),
+				},
+				AgedReceivables {
+					days_0_to_30: usd(25_00),
+					days_31_to_60: usd(20_00),
+					days_61_to_90: usd(0),
+					days_over_90: usd(5_00),
+					invoices: 3,
+					organization_id: organization.id,
+					total: usd(50_00),
+				},
+			]
+		);
+	}
+}
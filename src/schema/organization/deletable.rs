@@ -11,6 +11,10 @@ impl Deletable for PgOrganization
 	type Db = Postgres;
 	type Entity = Organization;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Organization", rows))
+	)]
 	async fn delete<'connection, 'entity, Conn, Iter>(
 		connection: Conn,
 		entities: Iter,
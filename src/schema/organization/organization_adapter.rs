@@ -7,6 +7,7 @@ use super::PgOrganization;
 #[async_trait::async_trait]
 impl OrganizationAdapter for PgOrganization
 {
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Organization")))]
 	async fn create<'connection, Conn>(
 		connection: Conn,
 		location: Location,
@@ -2,15 +2,97 @@ use clinvoice_adapter::{
 	fmt::{sql, QueryBuilderExt, TableToSql},
 	schema::columns::{LocationColumns, OrganizationColumns},
 	Retrievable,
-	WriteWhereClause,
+	WriteContext,
 };
 use clinvoice_match::MatchOrganization;
 use clinvoice_schema::Organization;
-use futures::TryStreamExt;
-use sqlx::{Pool, Postgres, Result};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
 
 use super::PgOrganization;
-use crate::{fmt::PgLocationRecursiveCte, schema::PgLocation, PgSchema};
+use crate::{
+	fmt::PgLocationRecursiveCte,
+	schema::{
+		count,
+		explain,
+		sort,
+		trace,
+		write_where_clause::WriteWhere,
+		Countable,
+		Explainable,
+		OrganizationSortKey,
+		Page,
+		Paginatable,
+		Pagination,
+		PgLocation,
+		RenderedQuery,
+		Sort,
+		Sortable,
+		StrComparable,
+		StrComparison,
+	},
+};
+
+const COLUMNS: OrganizationColumns<&'static str> = OrganizationColumns::default();
+
+/// Generate the query which retrieves all [`Organization`]s that match the `match_condition`, and
+/// the [`WriteContext`] which follows its `WHERE` clause.
+fn query(
+	match_condition: &MatchOrganization,
+	comparison: StrComparison,
+) -> (QueryBuilder<Postgres>, WriteContext)
+{
+	let mut query = PgLocation::query_with_recursive(&match_condition.location, comparison);
+
+	query.push(sql::SELECT).push_columns(&COLUMNS.default_scope());
+	let context = push_from_where(&mut query, match_condition, comparison);
+
+	(query, context)
+}
+
+/// Write the `FROM` and `WHERE` clauses which select the [`Organization`]s that match the
+/// `match_condition` to the `query`, and return the [`WriteContext`] which follows them.
+pub(super) fn push_from_where(
+	query: &mut QueryBuilder<Postgres>,
+	match_condition: &MatchOrganization,
+	comparison: StrComparison,
+) -> WriteContext
+{
+	let columns = COLUMNS.default_scope();
+	let location_columns = LocationColumns::default().default_scope();
+
+	query.push_default_from::<OrganizationColumns<char>>().push_equijoin(
+		PgLocationRecursiveCte::from(&match_condition.location),
+		LocationColumns::<char>::DEFAULT_ALIAS,
+		location_columns.id,
+		columns.location_id,
+	);
+
+	comparison.write_where(
+		Default::default(),
+		OrganizationColumns::<char>::DEFAULT_ALIAS,
+		match_condition,
+		query,
+	)
+}
+
+/// The (scoped) column which [`Organization`]s are sorted by for the `key`.
+fn sort_column(key: OrganizationSortKey) -> String
+{
+	let columns = COLUMNS.default_scope();
+	match key
+	{
+		OrganizationSortKey::Id => columns.id.to_string(),
+		OrganizationSortKey::Name => columns.name.to_string(),
+	}
+}
+
+/// Construct an [`Organization`] from each of the `rows`.
+async fn rows_to_views(connection: &Pool<Postgres>, rows: &[PgRow]) -> Result<Vec<Organization>>
+{
+	trace::rows(rows.len());
+	let locations = PgLocation::retrieve_for_rows(connection, COLUMNS.location_id, rows).await?;
+	rows.iter().map(|row| PgOrganization::row_to_view(COLUMNS, &locations, row)).collect()
+}
 
 /// Implementors of this trait are capable of being retrieved from a [`Database`].
 #[async_trait::async_trait]
@@ -29,36 +111,137 @@ impl Retrievable for PgOrganization
 		match_condition: Self::Match,
 	) -> Result<Vec<Self::Entity>>
 	{
-		const COLUMNS: OrganizationColumns<&'static str> = OrganizationColumns::default();
+		Self::retrieve_comparing(connection, match_condition, StrComparison::Exact).await
+	}
+}
 
+#[async_trait::async_trait]
+impl StrComparable for PgOrganization
+{
+	/// Retrieve all [`Organization`]s (via `connection`) that match the `match_condition`,
+	/// comparing strings according to the `comparison`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Organization", locations, rows))
+	)]
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, comparison);
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Paginatable for PgOrganization
+{
+	/// Retrieve the [`Page`] of [`Organization`]s (via `connection`) that match the
+	/// `match_condition` which is described by the `pagination`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Organization", locations, rows))
+	)]
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>
+	{
 		let columns = COLUMNS.default_scope();
-		let location_columns = LocationColumns::default().default_scope();
-		let mut query = PgLocation::query_with_recursive(&match_condition.location);
-
-		query
-			.push(sql::SELECT)
-			.push_columns(&columns)
-			.push_default_from::<OrganizationColumns<char>>()
-			.push_equijoin(
-				PgLocationRecursiveCte::from(&match_condition.location),
-				LocationColumns::<char>::DEFAULT_ALIAS,
-				location_columns.id,
-				columns.location_id,
-			);
-
-		PgSchema::write_where_clause(
-			Default::default(),
-			OrganizationColumns::<char>::DEFAULT_ALIAS,
-			&match_condition,
-			&mut query,
-		);
+		let (mut query, context) = query(&match_condition, StrComparison::Exact);
 
-		query
-			.prepare()
-			.fetch(connection)
-			.and_then(|row| async move { Self::row_to_view(connection, COLUMNS, &row).await })
-			.try_collect()
-			.await
+		pagination.push_where(&mut query, context, columns.id, None)?;
+		pagination.push_order_limit(&mut query, columns.id, None)?;
+
+		let mut rows = query.prepare().fetch_all(connection).await?;
+		let next = pagination.next(&mut rows, COLUMNS.id, None)?;
+
+		Ok(Page { entities: rows_to_views(connection, &rows).await?, next })
+	}
+}
+
+#[async_trait::async_trait]
+impl Sortable for PgOrganization
+{
+	type SortKey = OrganizationSortKey;
+
+	/// Retrieve all [`Organization`]s (via `connection`) that match the `match_condition`, ordered
+	/// by the `sort`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Organization", locations, rows))
+	)]
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		sort::push_order_by(&mut query, sort, sort_column, COLUMNS.default_scope().id);
+
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Countable for PgOrganization
+{
+	/// Count the [`Organization`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Organization")))]
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>
+	{
+		let mut query =
+			PgLocation::query_with_recursive(&match_condition.location, StrComparison::Exact);
+
+		query.push(sql::SELECT).push(count::COUNT);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+
+	/// Check whether there are any [`Organization`]s (via `connection`) that match the
+	/// `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Organization")))]
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>
+	{
+		let mut query =
+			PgLocation::query_with_recursive(&match_condition.location, StrComparison::Exact);
+
+		query.push(sql::SELECT).push(count::EXISTS);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+		query.push(')');
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+}
+
+#[async_trait::async_trait]
+impl Explainable for PgOrganization
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the query which retrieves the
+	/// [`Organization`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Organization")))]
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::explain(connection, &mut query).await
+	}
+
+	/// Render the query which retrieves the [`Organization`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Organization")))]
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>
+	{
+		let (mut query, _) = query(&match_condition, StrComparison::Exact);
+		explain::render(connection, &mut query).await
 	}
 }
 
@@ -11,6 +11,10 @@ impl Updatable for PgOrganization
 	type Db = Postgres;
 	type Entity = Organization;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Organization", rows))
+	)]
 	async fn update<'entity, Iter>(
 		connection: &mut Transaction<Self::Db>,
 		entities: Iter,
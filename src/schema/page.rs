@@ -0,0 +1,227 @@
+//! # Summary
+//!
+//! This module contains types which allow the results of a [`Retrievable`] to be retrieved one
+//! [`Page`] at a time.
+
+use core::fmt::Display;
+
+use clinvoice_adapter::{Retrievable, WriteContext};
+use clinvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+use sqlx::{postgres::PgRow, Error, Pool, Postgres, QueryBuilder, Result, Row};
+
+/// Which part of the results of a query to retrieve.
+///
+/// Keyset pagination (i.e. [`Pagination::Id`] and [`Pagination::TimeBegin`]) should be preferred
+/// over [`Pagination::Offset`], since the rows which were skipped do not have to be read, and rows
+/// which are created or deleted between requests do not cause other rows to be skipped or repeated.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Pagination
+{
+	/// Retrieve at most `limit` rows, in order of their `id`, whose `id` is greater than `after`.
+	/// When `after` is [`None`], start from the first row.
+	///
+	/// [`PgContact`](super::PgContact)s cannot be paginated this way, as they have no `id`.
+	Id
+	{
+		/// The `id` of the last row of the previous page.
+		after: Option<Id>,
+
+		/// The maximum number of rows to retrieve.
+		limit: u32,
+	},
+
+	/// Skip the first `offset` rows (in order of their `id`, or `label` for
+	/// [`PgContact`](super::PgContact)s), and then retrieve at most `limit` rows.
+	Offset
+	{
+		/// The maximum number of rows to retrieve.
+		limit: u32,
+
+		/// The number of rows to skip.
+		offset: u32,
+	},
+
+	/// Retrieve at most `limit` rows, in order of their `time_begin` and then `id`, which come
+	/// after the `time_begin` and `id` of `after`. When `after` is [`None`], start from the first
+	/// row.
+	///
+	/// Only [`PgTimesheet`](super::PgTimesheet)s can be paginated this way.
+	TimeBegin
+	{
+		/// The `time_begin` and `id` of the last row of the previous page.
+		after: Option<(DateTime<Utc>, Id)>,
+
+		/// The maximum number of rows to retrieve.
+		limit: u32,
+	},
+}
+
+impl Pagination
+{
+	/// The maximum number of rows to retrieve.
+	pub const fn limit(&self) -> u32
+	{
+		match self
+		{
+			Self::Id { limit, .. } | Self::Offset { limit, .. } | Self::TimeBegin { limit, .. } =>
+			{
+				*limit
+			},
+		}
+	}
+
+	/// Truncate the `rows` (which were retrieved with one more row than the [`limit`](Self::limit))
+	/// to the [`limit`](Self::limit), and return the [`Pagination`] for the page after them, or
+	/// [`None`] if there are no rows after them. There is never a page after one whose
+	/// [`limit`](Self::limit) is `0`, since it would be the same page again.
+	///
+	/// `id` and `time_begin` are the names of the respective columns of the `rows`.
+	pub(super) fn next(
+		&self,
+		rows: &mut Vec<PgRow>,
+		id: &str,
+		time_begin: Option<&str>,
+	) -> Result<Option<Self>>
+	{
+		let limit = self.limit() as usize;
+		if rows.len() <= limit
+		{
+			return Ok(None);
+		}
+
+		rows.truncate(limit);
+		let last = match rows.last()
+		{
+			Some(row) => row,
+
+			// NOTE: there is no last row when the `limit` is `0`
+			None => return Ok(None),
+		};
+
+		Ok(Some(match *self
+		{
+			Self::Id { limit, .. } => Self::Id { after: Some(last.try_get(id)?), limit },
+			Self::Offset { limit, offset } =>
+			{
+				Self::Offset { limit, offset: offset.saturating_add(limit) }
+			},
+			Self::TimeBegin { limit, .. } =>
+			{
+				let time_begin = time_begin.ok_or_else(time_begin_not_found)?;
+				let after = (last.try_get(time_begin)?, last.try_get(id)?);
+				Self::TimeBegin { after: Some(after), limit }
+			},
+		}))
+	}
+
+	/// Write `ORDER BY … LIMIT … [OFFSET …]` to the `query`, which retrieves one more row than
+	/// the [`limit`](Self::limit) so that [`Pagination::next`] can tell whether there is another
+	/// page.
+	///
+	/// `id` and `time_begin` are the (scoped) columns to order by.
+	pub(super) fn push_order_limit<Column>(
+		&self,
+		query: &mut QueryBuilder<Postgres>,
+		id: Column,
+		time_begin: Option<Column>,
+	) -> Result<()>
+	where
+		Column: Display,
+	{
+		query.push(" ORDER BY ");
+
+		if let Self::TimeBegin { .. } = self
+		{
+			query.push(time_begin.ok_or_else(time_begin_not_found)?).push(',');
+		}
+
+		query.push(id).push(" LIMIT ").push_bind(i64::from(self.limit()) + 1);
+
+		if let Self::Offset { offset, .. } = self
+		{
+			query.push(" OFFSET ").push_bind(i64::from(*offset));
+		}
+
+		Ok(())
+	}
+
+	/// Write the condition which excludes the rows of the previous pages to the `query`, if there
+	/// is one.
+	///
+	/// `id` and `time_begin` are the (scoped) columns to compare, and the rest of the args are the
+	/// same as [`WriteWhereClause::write_where_clause`](clinvoice_adapter::WriteWhereClause).
+	pub(super) fn push_where<Column>(
+		&self,
+		query: &mut QueryBuilder<Postgres>,
+		context: WriteContext,
+		id: Column,
+		time_begin: Option<Column>,
+	) -> Result<WriteContext>
+	where
+		Column: Display,
+	{
+		match self
+		{
+			Self::Id { after: Some(after), .. } =>
+			{
+				query.push(context).push(' ').push(id).push(" > ").push_bind(*after);
+			},
+			Self::TimeBegin { after: Some((after_time_begin, after_id)), .. } =>
+			{
+				query
+					.push(context)
+					.push(" (")
+					.push(time_begin.ok_or_else(time_begin_not_found)?)
+					.push(',')
+					.push(id)
+					.push(") > (")
+					.push_bind(*after_time_begin)
+					.push(',')
+					.push_bind(*after_id)
+					.push(')');
+			},
+			_ => return Ok(context),
+		};
+
+		Ok(WriteContext::AcceptingAnotherWhereCondition)
+	}
+}
+
+/// One page of the entities which were [retrieved](Paginatable::retrieve_page).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Page<T>
+{
+	/// The entities on this page.
+	pub entities: Vec<T>,
+
+	/// The [`Pagination`] which retrieves the next page, or [`None`] if this is the last page.
+	pub next: Option<Pagination>,
+}
+
+/// Implementors of this trait are capable of being retrieved from a [`Database`](sqlx::Database)
+/// one [`Page`] at a time.
+#[async_trait::async_trait]
+pub trait Paginatable: Retrievable<Db = Postgres>
+{
+	/// Retrieve the [`Page`] of [`Retrievable::Entity`]s (via `connection`) that match the
+	/// `match_condition` which is described by the `pagination`.
+	///
+	/// # Errors
+	///
+	/// * [`Error::ColumnNotFound`] if the `pagination` is [`Pagination::TimeBegin`] but the
+	///   entities have no `time_begin`.
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>;
+}
+
+/// The [`Error`] for when [`Pagination::TimeBegin`] is used on entities without a `time_begin`.
+fn time_begin_not_found() -> Error
+{
+	Error::ColumnNotFound("time_begin".into())
+}
@@ -0,0 +1,57 @@
+//! # Summary
+//!
+//! This module contains the [`Period`] which reports are grouped by.
+
+use core::fmt::Display;
+
+use sqlx::{Postgres, QueryBuilder};
+
+/// A span of the calendar which a report is grouped by.
+///
+/// Each period begins at midnight UTC, and weeks begin on Monday.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Period
+{
+	/// One day.
+	Day,
+
+	/// Seven days, beginning on Monday.
+	Week,
+
+	/// One calendar month.
+	Month,
+
+	/// Three calendar months, beginning in January, April, July, or October.
+	Quarter,
+
+	/// One calendar year.
+	Year,
+}
+
+impl Period
+{
+	/// The field of `date_trunc` which truncates a timestamp to the beginning of this period.
+	const fn field(self) -> &'static str
+	{
+		match self
+		{
+			Self::Day => "day",
+			Self::Week => "week",
+			Self::Month => "month",
+			Self::Quarter => "quarter",
+			Self::Year => "year",
+		}
+	}
+
+	/// Write `date_trunc(…)`, which truncates the `timestamp` to the beginning of this period in
+	/// UTC, to the `query`.
+	pub(super) fn push_date_trunc<Timestamp>(
+		self,
+		query: &mut QueryBuilder<Postgres>,
+		timestamp: Timestamp,
+	) where
+		Timestamp: Display,
+	{
+		query.push("date_trunc('").push(self.field()).push("', ").push(timestamp).push(", 'UTC')");
+	}
+}
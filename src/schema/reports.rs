@@ -0,0 +1,132 @@
+use sqlx::{Acquire, Executor, Postgres, Result};
+
+/// The materialized views which are created by the migrations, in the order they are refreshed.
+const REPORTS: [&str; 2] = ["report_client_revenue_by_month", "report_job_hours_by_day"];
+
+/// Refresh every view in [`REPORTS`]. When `concurrently`, the views may be read while they are
+/// being refreshed.
+pub(super) async fn refresh<'connection, Conn>(connection: Conn, concurrently: bool) -> Result<()>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	let mut transaction = connection.begin().await?;
+	let mode = if concurrently { " CONCURRENTLY" } else { "" };
+
+	for report in REPORTS
+	{
+		(&mut transaction)
+			.execute(format!("REFRESH MATERIALIZED VIEW{mode} {report};").as_str())
+			.await?;
+	}
+
+	transaction.commit().await
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::schema::{
+		EmployeeAdapter,
+		JobAdapter,
+		LocationAdapter,
+		OrganizationAdapter,
+		TimesheetAdapter,
+	};
+	use clinvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Invoice,
+		InvoiceDate,
+		Money,
+	};
+	use money2::Decimal;
+	use pretty_assertions::assert_eq;
+
+	use crate::{
+		schema::{util, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn refresh()
+	{
+		let connection = util::connect().await;
+		PgSchema::migrate(&connection).await.unwrap();
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Organization".into()).await.unwrap();
+		let employee =
+			PgEmployee::create(&connection, "My Name".into(), "Employed".into(), "Janitor".into())
+				.await
+				.unwrap();
+
+		let job = PgJob::create(
+			&connection,
+			organization.clone(),
+			None,
+			Utc.ymd(2022, 09, 01).and_hms(08, 00, 00),
+			Duration::from_secs(900),
+			Invoice {
+				date: Some(InvoiceDate {
+					issued: Utc.ymd(2022, 09, 30).and_hms(12, 00, 00),
+					paid:   None,
+				}),
+				hourly_rate: Money::new(20_00, 2, Currency::Usd),
+			},
+			String::new(),
+			"Report".into(),
+		)
+		.await
+		.unwrap();
+
+		// {{{
+		let mut transaction = connection.begin().await.unwrap();
+
+		PgTimesheet::create(
+			&mut transaction,
+			employee,
+			vec![("Travel".into(), Money::new(5_00, 2, Currency::Usd), "Bus".into())],
+			job.clone(),
+			Utc.ymd(2022, 09, 01).and_hms(09, 00, 00),
+			Some(Utc.ymd(2022, 09, 01).and_hms(10, 06, 00)),
+			String::new(),
+		)
+		.await
+		.unwrap();
+
+		transaction.commit().await.unwrap();
+		// }}}
+
+		PgSchema::refresh_reports(&connection, false).await.unwrap();
+
+		let hours: (Decimal, Decimal) = sqlx::query_as(
+			"SELECT hours, billable_hours FROM report_job_hours_by_day
+			WHERE job_id = $1 AND day = $2;",
+		)
+		.bind(job.id)
+		.bind(Utc.ymd(2022, 09, 01).and_hms(00, 00, 00))
+		.fetch_one(&connection)
+		.await
+		.unwrap();
+
+		assert_eq!(hours, (Decimal::new(1_1, 1), Decimal::new(1_25, 2)));
+
+		// NOTE: refreshing concurrently relies on the unique indexes
+		PgSchema::refresh_reports(&connection, true).await.unwrap();
+
+		let total: Decimal = sqlx::query_scalar(
+			"SELECT total FROM report_client_revenue_by_month
+			WHERE organization_id = $1 AND month = $2 AND currency = 'USD';",
+		)
+		.bind(organization.id)
+		.bind(Utc.ymd(2022, 09, 01).and_hms(00, 00, 00))
+		.fetch_one(&connection)
+		.await
+		.unwrap();
+
+		assert_eq!(total, Decimal::new(30_00, 2));
+	}
+}
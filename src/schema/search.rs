@@ -0,0 +1,68 @@
+//! # Summary
+//!
+//! This module contains a trait which allows the results of a [`Retrievable`] to be found by
+//! searching their text.
+
+use core::fmt::Display;
+
+use clinvoice_adapter::{Retrievable, WriteContext};
+use sqlx::{Pool, Postgres, QueryBuilder, Result};
+
+/// The name of the generated `tsvector` column of each searchable table.
+pub(super) const SEARCH_VECTOR: &str = "search_vector";
+
+/// Write `websearch_to_tsquery('english', {text})` to the `query`.
+fn push_tsquery(query: &mut QueryBuilder<Postgres>, text: &str)
+{
+	query.push("websearch_to_tsquery('english',").push_bind(text.to_owned()).push(')');
+}
+
+/// Write `ORDER BY ts_rank({search_vector}, {text}) DESC, {id}` to the `query`.
+pub(super) fn push_order_by_rank<Column, IdColumn>(
+	query: &mut QueryBuilder<Postgres>,
+	search_vector: Column,
+	text: &str,
+	id: IdColumn,
+) where
+	Column: Display,
+	IdColumn: Display,
+{
+	query.push(" ORDER BY ts_rank(").push(search_vector).push(',');
+	push_tsquery(query, text);
+	query.push(") DESC,").push(id);
+}
+
+/// Write `{context} {search_vector} @@ {text}` to the `query`, and return the [`WriteContext`]
+/// which follows it.
+pub(super) fn push_where<Column>(
+	query: &mut QueryBuilder<Postgres>,
+	context: WriteContext,
+	search_vector: Column,
+	text: &str,
+) -> WriteContext
+where
+	Column: Display,
+{
+	query.push(context).push(' ').push(search_vector).push(" @@ ");
+	push_tsquery(query, text);
+
+	WriteContext::AcceptingAnotherWhereCondition
+}
+
+/// Implementors of this trait are capable of being found in a [`Database`](sqlx::Database) by
+/// searching their text (e.g. [`Job::notes`](clinvoice_schema::Job::notes)).
+#[async_trait::async_trait]
+pub trait Searchable: Retrievable<Db = Postgres>
+{
+	/// Retrieve all [`Retrievable::Entity`]s (via `connection`) whose text matches the `text` and
+	/// that match the `match_condition`, with the most relevant first.
+	///
+	/// The `text` is parsed like a web search: words are stemmed (e.g. "migration" matches
+	/// "migrate"), `"quoted text"` matches a phrase, `or` matches either word, and `-` excludes a
+	/// word. Use [`Default::default`] as the `match_condition` to search every entity.
+	async fn search(
+		connection: &Pool<Postgres>,
+		text: &str,
+		match_condition: Self::Match,
+	) -> Result<Vec<Self::Entity>>;
+}
@@ -0,0 +1,198 @@
+//! # Summary
+//!
+//! This module contains types which allow the results of a [`Retrievable`] to be retrieved in a
+//! specific order.
+
+use core::fmt::Display;
+
+use clinvoice_adapter::Retrievable;
+use sqlx::{Pool, Postgres, QueryBuilder, Result};
+
+/// The key to sort [`Contact`](clinvoice_schema::Contact)s by.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ContactSortKey
+{
+	/// [`Contact::label`](clinvoice_schema::Contact::label)
+	Label,
+}
+
+/// The direction to [`Sort`] in.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Direction
+{
+	/// From least to greatest (e.g. `A` to `Z`). [`None`] values are last.
+	#[default]
+	Ascending,
+
+	/// From greatest to least (e.g. `Z` to `A`). [`None`] values are first.
+	Descending,
+}
+
+/// The key to sort [`Employee`](clinvoice_schema::Employee)s by.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum EmployeeSortKey
+{
+	/// [`Employee::id`](clinvoice_schema::Employee::id)
+	Id,
+
+	/// [`Employee::name`](clinvoice_schema::Employee::name)
+	Name,
+
+	/// [`Employee::status`](clinvoice_schema::Employee::status)
+	Status,
+
+	/// [`Employee::title`](clinvoice_schema::Employee::title)
+	Title,
+}
+
+/// The key to sort [`Expense`](clinvoice_schema::Expense)s by.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ExpenseSortKey
+{
+	/// [`Expense::category`](clinvoice_schema::Expense::category)
+	Category,
+
+	/// [`Expense::id`](clinvoice_schema::Expense::id)
+	Id,
+
+	/// [`Expense::timesheet_id`](clinvoice_schema::Expense::timesheet_id)
+	TimesheetId,
+}
+
+/// The key to sort [`Job`](clinvoice_schema::Job)s by.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum JobSortKey
+{
+	/// The [`Organization::name`](clinvoice_schema::Organization::name) of the
+	/// [`Job::client`](clinvoice_schema::Job::client).
+	ClientName,
+
+	/// [`Job::date_close`](clinvoice_schema::Job::date_close)
+	DateClose,
+
+	/// [`Job::date_open`](clinvoice_schema::Job::date_open)
+	DateOpen,
+
+	/// [`Job::id`](clinvoice_schema::Job::id)
+	Id,
+
+	/// [`InvoiceDate::issued`](clinvoice_schema::InvoiceDate::issued)
+	InvoiceDateIssued,
+
+	/// [`InvoiceDate::paid`](clinvoice_schema::InvoiceDate::paid)
+	InvoiceDatePaid,
+}
+
+/// The key to sort [`Location`](clinvoice_schema::Location)s by.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LocationSortKey
+{
+	/// [`Location::id`](clinvoice_schema::Location::id)
+	Id,
+
+	/// [`Location::name`](clinvoice_schema::Location::name)
+	Name,
+}
+
+/// The key to sort [`Organization`](clinvoice_schema::Organization)s by.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum OrganizationSortKey
+{
+	/// [`Organization::id`](clinvoice_schema::Organization::id)
+	Id,
+
+	/// [`Organization::name`](clinvoice_schema::Organization::name)
+	Name,
+}
+
+/// The key to sort [`Timesheet`](clinvoice_schema::Timesheet)s by.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TimesheetSortKey
+{
+	/// The [`Employee::name`](clinvoice_schema::Employee::name) of the
+	/// [`Timesheet::employee`](clinvoice_schema::Timesheet::employee).
+	EmployeeName,
+
+	/// [`Timesheet::id`](clinvoice_schema::Timesheet::id)
+	Id,
+
+	/// The [`Job::date_open`](clinvoice_schema::Job::date_open) of the
+	/// [`Timesheet::job`](clinvoice_schema::Timesheet::job).
+	JobDateOpen,
+
+	/// [`Timesheet::time_begin`](clinvoice_schema::Timesheet::time_begin)
+	TimeBegin,
+
+	/// [`Timesheet::time_end`](clinvoice_schema::Timesheet::time_end)
+	TimeEnd,
+}
+
+/// Sort by the `key` in the given `direction`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Sort<Key>
+{
+	/// The direction to sort the `key` in.
+	pub direction: Direction,
+
+	/// What to sort by.
+	pub key: Key,
+}
+
+impl<Key> Sort<Key>
+{
+	/// Sort by the `key` in [ascending](Direction::Ascending) order.
+	pub const fn asc(key: Key) -> Self
+	{
+		Self { direction: Direction::Ascending, key }
+	}
+
+	/// Sort by the `key` in [descending](Direction::Descending) order.
+	pub const fn desc(key: Key) -> Self
+	{
+		Self { direction: Direction::Descending, key }
+	}
+}
+
+/// Implementors of this trait are capable of being retrieved from a [`Database`](sqlx::Database)
+/// in a specific order.
+#[async_trait::async_trait]
+pub trait Sortable: Retrievable<Db = Postgres>
+{
+	/// What the [`Retrievable::Entity`]s can be sorted by.
+	type SortKey: Copy + Send + Sync;
+
+	/// Retrieve all [`Retrievable::Entity`]s (via `connection`) that match the `match_condition`,
+	/// ordered by each [`Sort`] in `sort` in turn.
+	///
+	/// Entities which are equal according to every [`Sort`] are ordered by their `id` (or `label`,
+	/// for [`PgContact`](super::PgContact)s), so that the order is always the same.
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>;
+}
+
+/// Write `ORDER BY {column(sort[0].key)} {sort[0].direction}, …, {id}` to the `query`.
+pub(super) fn push_order_by<Key, F, Column>(
+	query: &mut QueryBuilder<Postgres>,
+	sort: &[Sort<Key>],
+	column: F,
+	id: Column,
+) where
+	Key: Copy,
+	F: Fn(Key) -> String,
+	Column: Display,
+{
+	query.push(" ORDER BY ");
+
+	sort.iter().for_each(|s| {
+		query.push(column(s.key)).push(match s.direction
+		{
+			Direction::Ascending => " ASC,",
+			Direction::Descending => " DESC,",
+		});
+	});
+
+	query.push(id);
+}
@@ -0,0 +1,317 @@
+use core::fmt::Display;
+
+use clinvoice_adapter::Retrievable;
+use sqlx::{Acquire, Executor, Pool, Postgres, QueryBuilder, Result};
+
+use super::util::TEXT_COLUMNS;
+
+/// The immutable wrapper of `unaccent`, which (unlike `unaccent` itself) may be used in indexes.
+const UNACCENT: &str = "clinvoice_unaccent";
+
+/// How the strings of a [`MatchStr`](clinvoice_match::MatchStr) are compared to the strings in the
+/// database.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum StrComparison
+{
+	/// Strings must be exactly the same (e.g. `"organization"` does not match `"Organizatión"`).
+	#[default]
+	Exact,
+
+	/// Strings may differ in case (e.g. `"organizatión"` matches `"Organizatión"`).
+	///
+	/// [`MatchStr::Contains`](clinvoice_match::MatchStr::Contains) is only fast once
+	/// [`PgSchema::enable_trigram_search`](super::PgSchema::enable_trigram_search) has been
+	/// called.
+	CaseInsensitive,
+
+	/// Strings may differ in case and accents (e.g. `"organization"` matches `"Organizatión"`).
+	///
+	/// Requires the `pg_trgm` and `unaccent` extensions.
+	CaseAndAccentInsensitive,
+}
+
+impl StrComparison
+{
+	/// The name and definition (e.g. `USING gin (…)`) of each index on the `column` of the `table`
+	/// which this comparison uses.
+	///
+	/// The btree indexes only serve [`MatchStr::EqualTo`](clinvoice_match::MatchStr::EqualTo),
+	/// since a btree cannot serve a `LIKE` pattern which starts with `%`.
+	/// [`MatchStr::Contains`](clinvoice_match::MatchStr::Contains) and
+	/// [`MatchStr::Regex`](clinvoice_match::MatchStr::Regex) are served by trigram indexes instead:
+	/// the ones created by
+	/// [`PgSchema::enable_trigram_search`](super::PgSchema::enable_trigram_search)
+	/// for [`StrComparison::CaseInsensitive`], and the ones created here for
+	/// [`StrComparison::CaseAndAccentInsensitive`].
+	fn indexes(self, table: &str, column: &str) -> Vec<(String, String)>
+	{
+		match self
+		{
+			Self::Exact => Vec::new(),
+			Self::CaseInsensitive =>
+			{
+				vec![(format!("{table}__{column}_lower_idx"), format!("(lower({column}))"))]
+			},
+			Self::CaseAndAccentInsensitive => vec![
+				(
+					format!("{table}__{column}_unaccent_idx"),
+					format!("(lower({UNACCENT}({column})))"),
+				),
+				(
+					format!("{table}__{column}_unaccent_trgm_idx"),
+					format!("USING gin (lower({UNACCENT}({column})) gin_trgm_ops)"),
+				),
+			],
+		}
+	}
+
+	/// Write `{ident} = {value}` to the `query`, normalizing both sides according to `self`. The
+	/// `value` is sent as a bind parameter.
+	pub(super) fn push_equal<Ident>(
+		self,
+		query: &mut QueryBuilder<Postgres>,
+		ident: Ident,
+		value: String,
+	) where
+		Ident: Display,
+	{
+		self.push_normalized(query, |q| {
+			q.push(ident);
+		});
+		query.push('=');
+		self.push_normalized(query, |q| {
+			q.push_bind(value);
+		});
+	}
+
+	/// Write `{ident} LIKE {pattern}` to the `query`, ignoring case and accents according to
+	/// `self`. The `pattern` is sent as a bind parameter.
+	///
+	/// NOTE: `ILIKE` is used to ignore case (rather than `lower`), so that the trigram indexes on
+	///       the `ident` itself may serve the pattern.
+	pub(super) fn push_like<Ident>(
+		self,
+		query: &mut QueryBuilder<Postgres>,
+		ident: Ident,
+		pattern: String,
+	) where
+		Ident: Display,
+	{
+		match self
+		{
+			Self::Exact => query.push(ident).push(" LIKE ").push_bind(pattern),
+			Self::CaseInsensitive => query.push(ident).push(" ILIKE ").push_bind(pattern),
+			Self::CaseAndAccentInsensitive =>
+			{
+				self.push_normalized(query, |q| {
+					q.push(ident);
+				});
+				query.push(" LIKE ");
+				self.push_normalized(query, |q| {
+					q.push_bind(pattern);
+				});
+				query
+			},
+		};
+	}
+
+	/// Write `{ident} ~ {regex}` to the `query`, ignoring case and accents according to `self`.
+	/// The `regex` is sent as a bind parameter.
+	///
+	/// NOTE: the `regex` is not lowercased, since that would change its meaning (e.g. `\D` would
+	///       become `\d`). The case-insensitive operator `~*` is used instead. The `ident` is still
+	///       lowercased, so that the trigram index on that expression may serve the `regex`.
+	pub(super) fn push_regex<Ident>(
+		self,
+		query: &mut QueryBuilder<Postgres>,
+		ident: Ident,
+		regex: String,
+	) where
+		Ident: Display,
+	{
+		match self
+		{
+			Self::Exact => query.push(ident).push(" ~ ").push_bind(regex),
+			Self::CaseInsensitive => query.push(ident).push(" ~* ").push_bind(regex),
+			Self::CaseAndAccentInsensitive => query
+				.push("lower(")
+				.push(UNACCENT)
+				.push('(')
+				.push(ident)
+				.push(")) ~* ")
+				.push(UNACCENT)
+				.push('(')
+				.push_bind(regex)
+				.push(')'),
+		};
+	}
+
+	/// Write whatever `write` writes to the `query`, surrounded by the functions which normalize it
+	/// according to `self`.
+	fn push_normalized<F>(self, query: &mut QueryBuilder<Postgres>, write: F)
+	where
+		F: FnOnce(&mut QueryBuilder<Postgres>),
+	{
+		match self
+		{
+			Self::Exact => write(query),
+			Self::CaseInsensitive =>
+			{
+				query.push("lower(");
+				write(query);
+				query.push(')');
+			},
+			Self::CaseAndAccentInsensitive =>
+			{
+				query.push("lower(").push(UNACCENT).push('(');
+				write(query);
+				query.push("))");
+			},
+		}
+	}
+}
+
+/// Implementors of this trait are capable of being retrieved from a [`Database`](sqlx::Database)
+/// while comparing strings in a specific way.
+#[async_trait::async_trait]
+pub trait StrComparable: Retrievable<Db = Postgres>
+{
+	/// Retrieve all [`Retrievable::Entity`]s (via `connection`) that match the `match_condition`,
+	/// comparing the strings of each [`MatchStr`](clinvoice_match::MatchStr) according to the
+	/// `comparison`.
+	///
+	/// [`Retrievable::retrieve`] is the same as this with [`StrComparison::Exact`]. Whatever the
+	/// `comparison` needs must have been created by
+	/// [`PgSchema::enable_str_comparison`](super::PgSchema::enable_str_comparison) beforehand.
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>;
+}
+
+/// Create everything which the `comparison` needs in the database via `connection` (i.e. the
+/// extensions, functions, and the [indexes](StrComparison::indexes) on every column in
+/// [`TEXT_COLUMNS`]), if it does not already exist.
+pub(super) async fn setup<'connection, Conn>(
+	connection: Conn,
+	comparison: StrComparison,
+) -> Result<()>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	if comparison == StrComparison::Exact
+	{
+		return Ok(());
+	}
+
+	let mut transaction = connection.begin().await?;
+
+	if comparison == StrComparison::CaseAndAccentInsensitive
+	{
+		(&mut transaction).execute("CREATE EXTENSION IF NOT EXISTS pg_trgm;").await?;
+		(&mut transaction).execute("CREATE EXTENSION IF NOT EXISTS unaccent;").await?;
+
+		// NOTE: `unaccent` is only `STABLE` because it depends on the `search_path`, so the wrapper
+		//       must refer to the dictionary by its fully qualified name to be `IMMUTABLE`.
+		let schema: String = sqlx::query_scalar(
+			"SELECT quote_ident(N.nspname)
+			FROM pg_extension E
+			JOIN pg_namespace N ON (N.oid = E.extnamespace)
+			WHERE E.extname = 'unaccent';",
+		)
+		.fetch_one(&mut transaction)
+		.await?;
+
+		(&mut transaction)
+			.execute(
+				format!(
+					"CREATE OR REPLACE FUNCTION {UNACCENT}(text) RETURNS text
+					LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+					AS $$ SELECT {schema}.unaccent('{schema}.unaccent'::regdictionary, $1) $$;"
+				)
+				.as_str(),
+			)
+			.await?;
+	}
+
+	for (table, column) in TEXT_COLUMNS
+	{
+		for (index, definition) in comparison.indexes(table, column)
+		{
+			let create = format!("CREATE INDEX IF NOT EXISTS {index} ON {table} {definition};");
+			(&mut transaction).execute(create.as_str()).await?;
+		}
+	}
+
+	transaction.commit().await
+}
+
+#[cfg(test)]
+mod tests
+{
+	use clinvoice_adapter::schema::{LocationAdapter, OrganizationAdapter};
+	use clinvoice_match::{MatchOrganization, MatchStr};
+	use pretty_assertions::assert_eq;
+
+	use super::{StrComparable, StrComparison};
+	use crate::{
+		schema::{util, PgLocation, PgOrganization},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn retrieve_comparing()
+	{
+		let connection = util::connect().await;
+
+		// NOTE: enabling more than once is harmless
+		PgSchema::enable_str_comparison(&connection, StrComparison::CaseAndAccentInsensitive)
+			.await
+			.unwrap();
+		PgSchema::enable_str_comparison(&connection, StrComparison::CaseAndAccentInsensitive)
+			.await
+			.unwrap();
+
+		let earth = PgLocation::create(&connection, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&connection, earth, "Some Other Organizatión".into())
+				.await
+				.unwrap();
+
+		for (name, comparison, expected) in [
+			(MatchStr::Contains("organization".into()), StrComparison::Exact, Vec::new()),
+			(MatchStr::Contains("organization".into()), StrComparison::CaseInsensitive, Vec::new()),
+			(
+				MatchStr::Contains("organization".into()),
+				StrComparison::CaseAndAccentInsensitive,
+				vec![organization.clone()],
+			),
+			// NOTE: only ASCII letters differ in case, since how `lower` treats the others depends
+			//       on the `LC_CTYPE` of the database.
+			(MatchStr::EqualTo("SOME OTHER ORGANIZATIón".into()), StrComparison::Exact, Vec::new()),
+			(
+				MatchStr::EqualTo("SOME OTHER ORGANIZATIón".into()),
+				StrComparison::CaseInsensitive,
+				vec![organization.clone()],
+			),
+			(
+				MatchStr::Regex("^SOME OTHER ORGANIZATION$".into()),
+				StrComparison::CaseAndAccentInsensitive,
+				vec![organization.clone()],
+			),
+		]
+		{
+			assert_eq!(
+				PgOrganization::retrieve_comparing(
+					&connection,
+					MatchOrganization { id: organization.id.into(), name, ..Default::default() },
+					comparison,
+				)
+				.await
+				.unwrap(),
+				expected,
+			);
+		}
+	}
+}
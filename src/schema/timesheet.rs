@@ -3,17 +3,34 @@ mod retrievable;
 mod timesheet_adapter;
 mod updatable;
 
-use clinvoice_adapter::schema::columns::{
-	EmployeeColumns,
-	JobColumns,
-	OrganizationColumns,
-	TimesheetColumns,
+use std::collections::HashMap;
+
+use clinvoice_adapter::{
+	fmt::sql,
+	schema::columns::{EmployeeColumns, JobColumns, OrganizationColumns, TimesheetColumns},
+};
+use clinvoice_match::MatchTimesheet;
+use clinvoice_schema::{Expense, Id, Location, Timesheet};
+use money2::{Currency, Exchange};
+use sqlx::{
+	error::UnexpectedNullError,
+	postgres::PgRow,
+	Error,
+	Pool,
+	Postgres,
+	QueryBuilder,
+	Result,
+	Row,
 };
-use clinvoice_schema::{Expense, Timesheet};
-use money2::{Decimal, Money};
-use sqlx::{error::UnexpectedNullError, postgres::PgRow, Error, Executor, Postgres, Result, Row};
 
-use super::{util, PgEmployee, PgJob};
+use super::{
+	exchange_rates::RatesByDate,
+	util,
+	ExchangeRatesSource,
+	PgEmployee,
+	PgJob,
+	StrComparison,
+};
 
 /// Implementor of the [`TimesheetAdapter`](clinvoice_adapter::schema::TimesheetAdapter) for the
 /// [`Postgres`](sqlx::Postgres) database.
@@ -21,32 +38,86 @@ pub struct PgTimesheet;
 
 impl PgTimesheet
 {
-	pub(super) async fn row_to_view<
-		'connection,
-		Conn,
+	/// Exchange the [`Expense`]s and [`Job`](clinvoice_schema::Job) of each of the `timesheets`
+	/// into the given `currency`.
+	///
+	/// The [`Expense`]s are exchanged using the rates effective on the
+	/// [`Timesheet::time_begin`], and the [`Job`](clinvoice_schema::Job) as in [`PgJob::exchange`].
+	/// The rates are retrieved from the `source`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Timesheet", exchange_rates_us))
+	)]
+	pub async fn exchange<Iter>(
+		connection: &Pool<Postgres>,
+		source: &dyn ExchangeRatesSource,
+		timesheets: Iter,
+		currency: Currency,
+	) -> Result<Vec<Timesheet>>
+	where
+		Iter: IntoIterator<Item = Timesheet>,
+	{
+		let mut rates = RatesByDate::new(connection, source);
+		let mut exchanged = Vec::new();
+
+		for mut timesheet in timesheets
+		{
+			let expenses_rates = rates.on(timesheet.time_begin.naive_utc().date()).await?;
+			timesheet.expenses = timesheet
+				.expenses
+				.into_iter()
+				.map(|x| x.exchange(currency, expenses_rates))
+				.collect();
+
+			timesheet.job = PgJob::exchange_with(&mut rates, timesheet.job, currency).await?;
+			exchanged.push(timesheet);
+		}
+
+		Ok(exchanged)
+	}
+
+	/// Write `(SELECT … FROM … WHERE …)`, which selects the [`Id`] of every [`Timesheet`] that
+	/// matches the `match_condition`, to the `query`.
+	///
+	/// The `query` must begin with the [`PgLocation::query_with_recursive`] of the
+	/// `match_condition.job.client.location`.
+	///
+	/// [`PgLocation::query_with_recursive`]: super::PgLocation::query_with_recursive
+	pub(super) fn push_matching_ids(
+		query: &mut QueryBuilder<Postgres>,
+		match_condition: &MatchTimesheet,
+	)
+	{
+		query.push('(').push(sql::SELECT).push(TimesheetColumns::default().default_scope().id);
+		retrievable::push_from_where(query, match_condition, StrComparison::Exact);
+		query.push(')');
+	}
+
+	/// Construct a [`Timesheet`] from the `row`, where the [`Location`] of the client of the
+	/// [`Job`](clinvoice_schema::Job) must be among the `locations`.
+	pub(super) fn row_to_view<
 		TimesheetColumnNames,
 		EmployeeColumnNames,
 		ExpenseColumnNames,
 		JobColumnNames,
 		OrganizationColumnNames,
 	>(
-		connection: Conn,
 		columns: TimesheetColumns<TimesheetColumnNames>,
 		employee_columns: EmployeeColumns<EmployeeColumnNames>,
 		expenses_ident: ExpenseColumnNames,
 		job_columns: JobColumns<JobColumnNames>,
+		job_invoice_hourly_rate_currency: &str,
+		locations: &HashMap<Id, Location>,
 		organization_columns: OrganizationColumns<OrganizationColumnNames>,
 		row: &PgRow,
 	) -> Result<Timesheet>
 	where
-		Conn: Executor<'connection, Database = Postgres>,
 		EmployeeColumnNames: AsRef<str>,
 		JobColumnNames: AsRef<str>,
 		OrganizationColumnNames: AsRef<str>,
 		TimesheetColumnNames: AsRef<str>,
 		ExpenseColumnNames: AsRef<str>,
 	{
-		let job_fut = PgJob::row_to_view(connection, job_columns, organization_columns, row);
 		Ok(Timesheet {
 			employee: PgEmployee::row_to_view(employee_columns, row),
 			id: row.try_get(columns.id.as_ref())?,
@@ -55,21 +126,16 @@ impl PgTimesheet
 			work_notes: row.try_get(columns.work_notes.as_ref())?,
 			expenses: row
 				.try_get(expenses_ident.as_ref())
-				.and_then(|raw_expenses: Vec<(_, String, _, _, _)>| {
+				.and_then(|raw_expenses: Vec<(_, _, _, _, _, String)>| {
 					raw_expenses
 						.into_iter()
-						.map(|(category, cost, description, id, timesheet_id)| {
+						.map(|(category, cost, description, id, timesheet_id, cost_currency)| {
 							Ok(Expense {
 								category,
 								description,
 								id,
 								timesheet_id,
-								cost: Money {
-									amount: cost
-										.parse::<Decimal>()
-										.map_err(|e| util::finance_err_to_sqlx(e.into()))?,
-									..Default::default()
-								},
+								cost: util::money_from(cost, &cost_currency)?,
 							})
 						})
 						.collect::<Result<Vec<_>>>()
@@ -82,7 +148,13 @@ impl PgTimesheet
 					},
 					_ => Err(e),
 				})?,
-			job: job_fut.await?,
+			job: PgJob::row_to_view(
+				job_columns,
+				job_invoice_hourly_rate_currency,
+				locations,
+				organization_columns,
+				row,
+			)?,
 		})
 	}
 }
@@ -11,6 +11,10 @@ impl Deletable for PgTimesheet
 	type Db = Postgres;
 	type Entity = Timesheet;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Timesheet", rows))
+	)]
 	async fn delete<'connection, 'entity, Conn, Iter>(
 		connection: Conn,
 		entities: Iter,
@@ -51,7 +55,7 @@ mod tests
 		chrono::{TimeZone, Utc},
 		Invoice,
 	};
-	use money2::{Currency, Exchange, ExchangeRates, Money};
+	use money2::{Currency, Money};
 	use pretty_assertions::assert_eq;
 
 	use crate::schema::{
@@ -141,7 +145,6 @@ mod tests
 		assert!(PgJob::delete(&connection, [job].iter()).await.is_err());
 		PgTimesheet::delete(&connection, [&timesheet, &timesheet2].into_iter()).await.unwrap();
 
-		let exchange_rates = ExchangeRates::new().await.unwrap();
 		assert_eq!(
 			PgTimesheet::retrieve(
 				&connection,
@@ -152,7 +155,7 @@ mod tests
 			.unwrap()
 			.into_iter()
 			.as_slice(),
-			&[timesheet3.clone().exchange(Default::default(), &exchange_rates)],
+			&[timesheet3.clone()],
 		);
 
 		assert_eq!(
@@ -166,7 +169,7 @@ mod tests
 			})
 			.await
 			.unwrap(),
-			timesheet3.expenses.exchange(Default::default(), &exchange_rates),
+			timesheet3.expenses,
 		);
 	}
 }
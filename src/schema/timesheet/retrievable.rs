@@ -9,21 +9,236 @@ use clinvoice_adapter::{
 		TimesheetColumns,
 	},
 	Retrievable,
-	WriteWhereClause,
+	WriteContext,
 };
 use clinvoice_match::MatchTimesheet;
 use clinvoice_schema::Timesheet;
-use futures::{TryFutureExt, TryStreamExt};
-use money2::{Exchange, ExchangeRates};
-use sqlx::{Pool, Postgres, Result};
+use sqlx::{postgres::PgRow, Pool, Postgres, QueryBuilder, Result, Row};
 
 use super::PgTimesheet;
 use crate::{
 	fmt::PgLocationRecursiveCte,
-	schema::{util, PgLocation},
-	PgSchema,
+	schema::{
+		count,
+		expenses::COST_CURRENCY,
+		explain,
+		job::INVOICE_HOURLY_RATE_CURRENCY,
+		search::{self, SEARCH_VECTOR},
+		sort,
+		trace,
+		write_where_clause::WriteWhere,
+		Countable,
+		Explainable,
+		Page,
+		Paginatable,
+		Pagination,
+		PgLocation,
+		RenderedQuery,
+		Searchable,
+		Sort,
+		Sortable,
+		StrComparable,
+		StrComparison,
+		TimesheetSortKey,
+	},
 };
 
+const COLUMNS: TimesheetColumns<&str> = TimesheetColumns::default();
+
+const EXPENSES_AGGREGATED_IDENT: &str = "expenses_aggregated";
+const JOB_INVOICE_HOURLY_RATE_CURRENCY_UNIQUE: &str = "job_invoice_hourly_rate_currency";
+
+const EMPLOYEE_COLUMNS_UNIQUE: EmployeeColumns<&str> = EmployeeColumns::unique();
+const JOB_COLUMNS_UNIQUE: JobColumns<&str> = JobColumns::unique();
+const ORGANIZATION_COLUMNS_UNIQUE: OrganizationColumns<&str> = OrganizationColumns::unique();
+
+/// Generate the query which retrieves all [`Timesheet`]s that match the `match_condition`, up to
+/// and including its `GROUP BY` clause.
+///
+/// The `pagination` and the `search` text (if any) are written into the `WHERE` clause.
+fn query(
+	match_condition: &MatchTimesheet,
+	pagination: Option<&Pagination>,
+	search: Option<&str>,
+	comparison: StrComparison,
+) -> Result<QueryBuilder<Postgres>>
+{
+	let columns = COLUMNS.default_scope();
+	let employee_columns = EmployeeColumns::default().default_scope();
+	let expense_columns = ExpenseColumns::default().default_scope();
+	let job_columns = JobColumns::default().default_scope();
+	let mut query =
+		PgLocation::query_with_recursive(&match_condition.job.client.location, comparison);
+	let organization_columns = OrganizationColumns::default().default_scope();
+
+	query
+		.push(sql::SELECT)
+		.push_columns(&columns)
+		.push_more_columns(&employee_columns.r#as(EMPLOYEE_COLUMNS_UNIQUE))
+		.push(",array_agg((") // NOTE: might need `",array_agg( DISTINCT ("`
+		.push_columns(&expense_columns)
+		.push(',')
+		.push(ExpenseColumns::<char>::DEFAULT_ALIAS)
+		.push('.')
+		.push(COST_CURRENCY)
+		.push("))")
+		.push(sql::AS)
+		.push(EXPENSES_AGGREGATED_IDENT)
+		.push_more_columns(&job_columns.r#as(JOB_COLUMNS_UNIQUE))
+		.push(',')
+		.push(JobColumns::<char>::DEFAULT_ALIAS)
+		.push('.')
+		.push(INVOICE_HOURLY_RATE_CURRENCY)
+		.push(sql::AS)
+		.push(JOB_INVOICE_HOURLY_RATE_CURRENCY_UNIQUE)
+		.push_more_columns(&organization_columns.r#as(ORGANIZATION_COLUMNS_UNIQUE));
+
+	let mut context = push_from_where(&mut query, match_condition, comparison);
+
+	if let Some(p) = pagination
+	{
+		context = p.push_where(&mut query, context, columns.id, Some(columns.time_begin))?;
+	}
+
+	if let Some(text) = search
+	{
+		search::push_where(&mut query, context, search_vector(), text);
+	}
+
+	query
+		.push(sql::GROUP_BY)
+		.separated(',')
+		.push(columns.id)
+		.push(employee_columns.id)
+		.push(job_columns.id)
+		.push(organization_columns.id);
+
+	Ok(query)
+}
+
+/// The (scoped) [`SEARCH_VECTOR`] column of the [`Timesheet`]s.
+fn search_vector() -> String
+{
+	format!("{}.{SEARCH_VECTOR}", TimesheetColumns::<char>::DEFAULT_ALIAS)
+}
+
+/// Write the `FROM` and `WHERE` clauses which select the [`Timesheet`]s that match the
+/// `match_condition` to the `query`, and return the [`WriteContext`] which follows them.
+///
+/// Since [`Expense`](clinvoice_schema::Expense)s are joined, there is one row for each of them.
+/// The `query` must begin with [`PgLocation::query_with_recursive`].
+pub(super) fn push_from_where(
+	query: &mut QueryBuilder<Postgres>,
+	match_condition: &MatchTimesheet,
+	comparison: StrComparison,
+) -> WriteContext
+{
+	let columns = COLUMNS.default_scope();
+	let employee_columns = EmployeeColumns::default().default_scope();
+	let expense_columns = ExpenseColumns::default().default_scope();
+	let job_columns = JobColumns::default().default_scope();
+	let location_columns = LocationColumns::default().default_scope();
+	let match_location = &match_condition.job.client.location;
+	let organization_columns = OrganizationColumns::default().default_scope();
+
+	query
+		.push_default_from::<TimesheetColumns<char>>()
+		.push_default_equijoin::<EmployeeColumns<char>, _, _>(
+			employee_columns.id,
+			columns.employee_id,
+		)
+		.push(sql::LEFT)
+		.push_default_equijoin::<ExpenseColumns<char>, _, _>(
+			expense_columns.timesheet_id,
+			columns.id,
+		)
+		.push_default_equijoin::<JobColumns<char>, _, _>(job_columns.id, columns.job_id)
+		.push_default_equijoin::<OrganizationColumns<char>, _, _>(
+			organization_columns.id,
+			job_columns.client_id,
+		)
+		.push_equijoin(
+			PgLocationRecursiveCte::from(match_location),
+			LocationColumns::<char>::DEFAULT_ALIAS,
+			location_columns.id,
+			organization_columns.location_id,
+		);
+
+	comparison.write_where(
+		comparison.write_where(
+			comparison.write_where(
+				comparison.write_where(
+					comparison.write_where(
+						Default::default(),
+						TimesheetColumns::<char>::DEFAULT_ALIAS,
+						match_condition,
+						query,
+					),
+					EmployeeColumns::<char>::DEFAULT_ALIAS,
+					&match_condition.employee,
+					query,
+				),
+				ExpenseColumns::<char>::DEFAULT_ALIAS,
+				&match_condition.expenses,
+				query,
+			),
+			JobColumns::<char>::DEFAULT_ALIAS,
+			&match_condition.job,
+			query,
+		),
+		OrganizationColumns::<char>::DEFAULT_ALIAS,
+		&match_condition.job.client,
+		query,
+	)
+}
+
+/// The (scoped) column which [`Timesheet`]s are sorted by for the `key`.
+///
+/// Every column is either in the `GROUP BY` clause of the [`query`], or is functionally dependent
+/// on one which is.
+fn sort_column(key: TimesheetSortKey) -> String
+{
+	let columns = COLUMNS.default_scope();
+	match key
+	{
+		TimesheetSortKey::EmployeeName =>
+		{
+			EmployeeColumns::default().default_scope().name.to_string()
+		},
+		TimesheetSortKey::Id => columns.id.to_string(),
+		TimesheetSortKey::JobDateOpen =>
+		{
+			JobColumns::default().default_scope().date_open.to_string()
+		},
+		TimesheetSortKey::TimeBegin => columns.time_begin.to_string(),
+		TimesheetSortKey::TimeEnd => columns.time_end.to_string(),
+	}
+}
+
+/// Construct a [`Timesheet`] from each of the `rows`.
+async fn rows_to_views(connection: &Pool<Postgres>, rows: &[PgRow]) -> Result<Vec<Timesheet>>
+{
+	trace::rows(rows.len());
+	let locations =
+		PgLocation::retrieve_for_rows(connection, ORGANIZATION_COLUMNS_UNIQUE.location_id, rows)
+			.await?;
+
+	rows.iter()
+		.map(|row| {
+			PgTimesheet::row_to_view(
+				COLUMNS,
+				EMPLOYEE_COLUMNS_UNIQUE,
+				EXPENSES_AGGREGATED_IDENT,
+				JOB_COLUMNS_UNIQUE,
+				JOB_INVOICE_HOURLY_RATE_CURRENCY_UNIQUE,
+				&locations,
+				ORGANIZATION_COLUMNS_UNIQUE,
+				row,
+			)
+		})
+		.collect()
+}
+
 /// Implementors of this trait are capable of being retrieved from a [`Database`].
 #[async_trait::async_trait]
 impl Retrievable for PgTimesheet
@@ -41,114 +256,166 @@ impl Retrievable for PgTimesheet
 		match_condition: Self::Match,
 	) -> Result<Vec<Self::Entity>>
 	{
-		const COLUMNS: TimesheetColumns<&str> = TimesheetColumns::default();
-
-		const EXPENSES_AGGREGATED_IDENT: &str = "expenses_aggregated";
+		Self::retrieve_comparing(connection, match_condition, StrComparison::Exact).await
+	}
+}
 
-		const EMPLOYEE_COLUMNS_UNIQUE: EmployeeColumns<&str> = EmployeeColumns::unique();
-		const JOB_COLUMNS_UNIQUE: JobColumns<&str> = JobColumns::unique();
-		const ORGANIZATION_COLUMNS_UNIQUE: OrganizationColumns<&str> =
-			OrganizationColumns::unique();
+#[async_trait::async_trait]
+impl StrComparable for PgTimesheet
+{
+	/// Retrieve all [`Timesheet`]s (via `connection`) that match the `match_condition`, comparing
+	/// strings according to the `comparison`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Timesheet", locations, rows))
+	)]
+	async fn retrieve_comparing(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		comparison: StrComparison,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let rows = query(&match_condition, None, None, comparison)?
+			.prepare()
+			.fetch_all(connection)
+			.await?;
+		rows_to_views(connection, &rows).await
+	}
+}
 
+#[async_trait::async_trait]
+impl Paginatable for PgTimesheet
+{
+	/// Retrieve the [`Page`] of [`Timesheet`]s (via `connection`) that match the `match_condition`
+	/// which is described by the `pagination`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Timesheet", locations, rows))
+	)]
+	async fn retrieve_page(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		pagination: Pagination,
+	) -> Result<Page<Self::Entity>>
+	{
 		let columns = COLUMNS.default_scope();
-		let employee_columns = EmployeeColumns::default().default_scope();
-		let exchange_rates_fut = ExchangeRates::new().map_err(util::finance_err_to_sqlx);
-		let expense_columns = ExpenseColumns::default().default_scope();
-		let job_columns = JobColumns::default().default_scope();
-		let location_columns = LocationColumns::default().default_scope();
-		let match_location = match_condition.job.client.location.clone();
-		let mut query = PgLocation::query_with_recursive(&match_location);
-		let organization_columns = OrganizationColumns::default().default_scope();
-
-		query
-			.push(sql::SELECT)
-			.push_columns(&columns)
-			.push_more_columns(&employee_columns.r#as(EMPLOYEE_COLUMNS_UNIQUE))
-			.push(",array_agg((") // NOTE: might need `",array_agg( DISTINCT ("`
-			.push_columns(&expense_columns)
-			.push("))")
-			.push(sql::AS)
-			.push(EXPENSES_AGGREGATED_IDENT)
-			.push_more_columns(&job_columns.r#as(JOB_COLUMNS_UNIQUE))
-			.push_more_columns(&organization_columns.r#as(ORGANIZATION_COLUMNS_UNIQUE))
-			.push_default_from::<TimesheetColumns<char>>()
-			.push_default_equijoin::<EmployeeColumns<char>, _, _>(
-				employee_columns.id,
-				columns.employee_id,
-			)
-			.push(sql::LEFT)
-			.push_default_equijoin::<ExpenseColumns<char>, _, _>(
-				expense_columns.timesheet_id,
-				columns.id,
-			)
-			.push_default_equijoin::<JobColumns<char>, _, _>(job_columns.id, columns.job_id)
-			.push_default_equijoin::<OrganizationColumns<char>, _, _>(
-				organization_columns.id,
-				job_columns.client_id,
-			)
-			.push_equijoin(
-				PgLocationRecursiveCte::from(&match_location),
-				LocationColumns::<char>::DEFAULT_ALIAS,
-				location_columns.id,
-				organization_columns.location_id,
-			);
-
-		let exchanged_condition = exchange_rates_fut
-			.await
-			.map(|rates| match_condition.exchange(Default::default(), &rates))?;
-
-		PgSchema::write_where_clause(
-			PgSchema::write_where_clause(
-				PgSchema::write_where_clause(
-					PgSchema::write_where_clause(
-						PgSchema::write_where_clause(
-							Default::default(),
-							TimesheetColumns::<char>::DEFAULT_ALIAS,
-							&exchanged_condition,
-							&mut query,
-						),
-						EmployeeColumns::<char>::DEFAULT_ALIAS,
-						&exchanged_condition.employee,
-						&mut query,
-					),
-					ExpenseColumns::<char>::DEFAULT_ALIAS,
-					&exchanged_condition.expenses,
-					&mut query,
-				),
-				JobColumns::<char>::DEFAULT_ALIAS,
-				&exchanged_condition.job,
-				&mut query,
-			),
-			OrganizationColumns::<char>::DEFAULT_ALIAS,
-			&exchanged_condition.job.client,
-			&mut query,
+		let mut query = query(&match_condition, Some(&pagination), None, StrComparison::Exact)?;
+
+		pagination.push_order_limit(&mut query, columns.id, Some(columns.time_begin))?;
+
+		let mut rows = query.prepare().fetch_all(connection).await?;
+		let next = pagination.next(&mut rows, COLUMNS.id, Some(COLUMNS.time_begin))?;
+
+		Ok(Page { entities: rows_to_views(connection, &rows).await?, next })
+	}
+}
+
+#[async_trait::async_trait]
+impl Sortable for PgTimesheet
+{
+	type SortKey = TimesheetSortKey;
+
+	/// Retrieve all [`Timesheet`]s (via `connection`) that match the `match_condition`, ordered by
+	/// the `sort`.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Timesheet", locations, rows))
+	)]
+	async fn retrieve_sorted(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+		sort: &[Sort<Self::SortKey>],
+	) -> Result<Vec<Self::Entity>>
+	{
+		let mut query = query(&match_condition, None, None, StrComparison::Exact)?;
+		sort::push_order_by(&mut query, sort, sort_column, COLUMNS.default_scope().id);
+
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Countable for PgTimesheet
+{
+	/// Count the [`Timesheet`]s (via `connection`) that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Timesheet")))]
+	async fn count(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<i64>
+	{
+		let mut query = PgLocation::query_with_recursive(
+			&match_condition.job.client.location,
+			StrComparison::Exact,
 		);
 
-		query
-			.push(sql::GROUP_BY)
-			.separated(',')
-			.push(columns.id)
-			.push(employee_columns.id)
-			.push(job_columns.id)
-			.push(organization_columns.id);
+		// NOTE: there is one row per `Expense`, rather than per `Timesheet`
+		query.push(sql::SELECT).push("count(DISTINCT ").push(COLUMNS.default_scope().id).push(')');
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
 
-		query
-			.prepare()
-			.fetch(connection)
-			.and_then(|row| async move {
-				Self::row_to_view(
-					connection,
-					COLUMNS,
-					EMPLOYEE_COLUMNS_UNIQUE,
-					EXPENSES_AGGREGATED_IDENT,
-					JOB_COLUMNS_UNIQUE,
-					ORGANIZATION_COLUMNS_UNIQUE,
-					&row,
-				)
-				.await
-			})
-			.try_collect()
-			.await
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+
+	/// Check whether there are any [`Timesheet`]s (via `connection`) that match the
+	/// `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Timesheet")))]
+	async fn exists(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<bool>
+	{
+		let mut query = PgLocation::query_with_recursive(
+			&match_condition.job.client.location,
+			StrComparison::Exact,
+		);
+
+		query.push(sql::SELECT).push(count::EXISTS);
+		push_from_where(&mut query, &match_condition, StrComparison::Exact);
+		query.push(')');
+
+		query.prepare().fetch_one(connection).await?.try_get(0)
+	}
+}
+
+#[async_trait::async_trait]
+impl Searchable for PgTimesheet
+{
+	/// Retrieve all [`Timesheet`]s (via `connection`) whose `work_notes` match the `text` and that
+	/// match the `match_condition`, with the most relevant first.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Timesheet", locations, rows))
+	)]
+	async fn search(
+		connection: &Pool<Postgres>,
+		text: &str,
+		match_condition: Self::Match,
+	) -> Result<Vec<Self::Entity>>
+	{
+		let mut query = query(&match_condition, None, Some(text), StrComparison::Exact)?;
+		search::push_order_by_rank(&mut query, search_vector(), text, COLUMNS.default_scope().id);
+
+		let rows = query.prepare().fetch_all(connection).await?;
+		rows_to_views(connection, &rows).await
+	}
+}
+
+#[async_trait::async_trait]
+impl Explainable for PgTimesheet
+{
+	/// Run `EXPLAIN (ANALYZE, FORMAT JSON)` on the query which retrieves the
+	/// [`Timesheet`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Timesheet")))]
+	async fn explain(connection: &Pool<Postgres>, match_condition: Self::Match) -> Result<String>
+	{
+		let mut query = query(&match_condition, None, None, StrComparison::Exact)?;
+		explain::explain(connection, &mut query).await
+	}
+
+	/// Render the query which retrieves the [`Timesheet`]s that match the `match_condition`.
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Timesheet")))]
+	async fn render(
+		connection: &Pool<Postgres>,
+		match_condition: Self::Match,
+	) -> Result<RenderedQuery>
+	{
+		let mut query = query(&match_condition, None, None, StrComparison::Exact)?;
+		explain::render(connection, &mut query).await
 	}
 }
 
@@ -175,9 +442,9 @@ mod tests
 		InvoiceDate,
 		Money,
 	};
-	use money2::{Exchange, ExchangeRates};
 	use pretty_assertions::assert_eq;
 
+	use super::{Countable, Paginatable, Pagination, Sort, Sortable, TimesheetSortKey};
 	use crate::schema::{util, PgEmployee, PgJob, PgLocation, PgOrganization, PgTimesheet};
 
 	#[tokio::test]
@@ -276,8 +543,6 @@ mod tests
 		transaction.commit().await.unwrap();
 		// }}}
 
-		let exchange_rates = ExchangeRates::new().await.unwrap();
-
 		assert_eq!(
 			PgTimesheet::retrieve(&connection, MatchTimesheet {
 				expenses: MatchSet::Not(MatchSet::Contains(Default::default()).into()),
@@ -292,7 +557,67 @@ mod tests
 			.unwrap()
 			.into_iter()
 			.as_slice(),
-			&[timesheet.exchange(Default::default(), &exchange_rates)],
+			&[timesheet.clone()],
+		);
+
+		let match_condition = MatchTimesheet {
+			id: Match::Or(vec![timesheet.id.into(), timesheet2.id.into()]),
+			..Default::default()
+		};
+
+		assert_eq!(PgTimesheet::count(&connection, match_condition.clone()).await.unwrap(), 2);
+		assert!(PgTimesheet::exists(&connection, match_condition.clone()).await.unwrap());
+
+		// `timesheet2` began first
+		let page = PgTimesheet::retrieve_page(
+			&connection,
+			match_condition.clone(),
+			Pagination::TimeBegin { after: None, limit: 1 },
+		)
+		.await
+		.unwrap();
+
+		assert_eq!(page.entities.iter().map(|t| t.id).collect::<Vec<_>>(), [timesheet2.id]);
+		assert_eq!(
+			page.next,
+			Some(Pagination::TimeBegin {
+				after: Some((timesheet2.time_begin, timesheet2.id)),
+				limit: 1,
+			}),
+		);
+
+		let page =
+			PgTimesheet::retrieve_page(&connection, match_condition.clone(), page.next.unwrap())
+				.await
+				.unwrap();
+
+		assert_eq!(page.entities.iter().map(|t| t.id).collect::<Vec<_>>(), [timesheet.id]);
+		assert_eq!(page.next, None);
+
+		assert_eq!(
+			PgTimesheet::retrieve_sorted(&connection, match_condition.clone(), &[
+				Sort::desc(TimesheetSortKey::TimeBegin),
+				Sort::asc(TimesheetSortKey::EmployeeName),
+			])
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|t| t.id)
+			.collect::<Vec<_>>(),
+			[timesheet.id, timesheet2.id],
+		);
+
+		// "Another Gúy" < "My Name"
+		assert_eq!(
+			PgTimesheet::retrieve_sorted(&connection, match_condition, &[Sort::asc(
+				TimesheetSortKey::EmployeeName
+			)])
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|t| t.id)
+			.collect::<Vec<_>>(),
+			[timesheet2.id, timesheet.id],
 		);
 	}
 }
@@ -6,14 +6,18 @@ use clinvoice_schema::{
 	Timesheet,
 };
 use money2::Money;
-use sqlx::{Postgres, Result, Transaction};
+use sqlx::{Acquire, Postgres, Result, Transaction};
 
 use super::PgTimesheet;
-use crate::{fmt::DateTimeExt, schema::PgExpenses};
+use crate::{
+	fmt::DateTimeExt,
+	schema::{timesheet_overlap, PgExpenses},
+};
 
 #[async_trait::async_trait]
 impl TimesheetAdapter for PgTimesheet
 {
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(entity = "Timesheet")))]
 	async fn create(
 		connection: &mut Transaction<Postgres>,
 		employee: Employee,
@@ -24,7 +28,10 @@ impl TimesheetAdapter for PgTimesheet
 		work_notes: String,
 	) -> Result<Timesheet>
 	{
-		let row = sqlx::query!(
+		// NOTE: inserted in a savepoint, so that the `connection` can still be used to find which
+		//       timesheet was overlapped if the insert fails.
+		let mut savepoint = connection.begin().await?;
+		let result = sqlx::query!(
 			"INSERT INTO timesheets
 				(employee_id, job_id, time_begin, time_end, work_notes)
 			VALUES
@@ -36,8 +43,23 @@ impl TimesheetAdapter for PgTimesheet
 			time_end,
 			work_notes,
 		)
-		.fetch_one(&mut *connection)
-		.await?;
+		.fetch_one(&mut savepoint)
+		.await;
+
+		let row = match result
+		{
+			Ok(row) =>
+			{
+				savepoint.commit().await?;
+				row
+			},
+			Err(e) =>
+			{
+				let rollback = savepoint.rollback().await;
+				let written = [(None, employee.id, time_begin, time_end)];
+				return Err(timesheet_overlap::map_err(connection, rollback, written, e).await);
+			},
+		};
 
 		let expenses_db = PgExpenses::create(connection, expenses, row.id).await?;
 
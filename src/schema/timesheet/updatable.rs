@@ -1,10 +1,10 @@
 use clinvoice_adapter::{schema::columns::TimesheetColumns, Updatable};
 use clinvoice_schema::{Expense, Timesheet};
-use sqlx::{Postgres, Result, Transaction};
+use sqlx::{Acquire, Postgres, Result, Transaction};
 
 use super::PgTimesheet;
 use crate::{
-	schema::{PgEmployee, PgExpenses, PgJob},
+	schema::{timesheet_overlap, PgEmployee, PgExpenses, PgJob},
 	PgSchema,
 };
 
@@ -14,6 +14,10 @@ impl Updatable for PgTimesheet
 	type Db = Postgres;
 	type Entity = Timesheet;
 
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(skip_all, fields(entity = "Timesheet", rows))
+	)]
 	async fn update<'entity, Iter>(
 		connection: &mut Transaction<Self::Db>,
 		entities: Iter,
@@ -32,7 +36,10 @@ impl Updatable for PgTimesheet
 			return Ok(());
 		}
 
-		PgSchema::update(connection, TimesheetColumns::default(), |query| {
+		// NOTE: updated in a savepoint, so that the `connection` can still be used to find which
+		//       timesheet was overlapped if the update fails.
+		let mut savepoint = connection.begin().await?;
+		let result = PgSchema::update(&mut savepoint, TimesheetColumns::default(), |query| {
 			query.push_values(peekable_entities, |mut q, e| {
 				q.push_bind(e.employee.id)
 					.push_bind(e.id)
@@ -42,7 +49,16 @@ impl Updatable for PgTimesheet
 					.push_bind(&e.work_notes);
 			});
 		})
-		.await?;
+		.await;
+
+		if let Err(e) = result
+		{
+			let rollback = savepoint.rollback().await;
+			let written = entities.map(|t| (Some(t.id), t.employee.id, t.time_begin, t.time_end));
+			return Err(timesheet_overlap::map_err(connection, rollback, written, e).await);
+		}
+
+		savepoint.commit().await?;
 
 		let employees = entities.clone().map(|e| &e.employee);
 
@@ -0,0 +1,429 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::{borrow::Cow, error::Error as StdError};
+
+use clinvoice_adapter::fmt::QueryBuilderExt;
+use clinvoice_schema::{
+	chrono::{DateTime, Utc},
+	Id,
+};
+use sqlx::{
+	error::DatabaseError,
+	Acquire,
+	Error,
+	Executor,
+	Postgres,
+	QueryBuilder,
+	Result,
+	Row,
+	Transaction,
+};
+
+/// The name of the exclusion constraint created by [`enable`].
+const CONSTRAINT: &str = "timesheets__employee_time_excl";
+
+/// The `id` (or [`None`] if it is being created), `employee_id`, `time_begin`, and `time_end` of a
+/// [`Timesheet`](clinvoice_schema::Timesheet) which is being written.
+pub(super) type Written = (Option<Id>, Id, DateTime<Utc>, Option<DateTime<Utc>>);
+
+/// A [`Timesheet`](clinvoice_schema::Timesheet) could not be written because it overlapped another
+/// of the same [`Employee`](clinvoice_schema::Employee), while
+/// [overlap exclusion](super::PgSchema::enable_timesheet_overlap_exclusion) was enabled.
+///
+/// It is returned as an [`Error::Database`], which can be downcast to this type (e.g. via
+/// `try_downcast_ref`). The [`code`](DatabaseError::code) and
+/// [`constraint`](DatabaseError::constraint) are those reported by the database.
+#[derive(Debug)]
+pub struct OverlappingTimesheet
+{
+	/// The [`Id`] of the [`Timesheet`](clinvoice_schema::Timesheet) which was overlapped.
+	pub conflicting_id: Id,
+
+	/// The [`Id`] of the [`Employee`](clinvoice_schema::Employee) who both
+	/// [`Timesheet`](clinvoice_schema::Timesheet)s belong to.
+	pub employee_id: Id,
+
+	/// The [`Id`] of the [`Timesheet`](clinvoice_schema::Timesheet) which could not be written, or
+	/// [`None`] if it was being created.
+	pub timesheet_id: Option<Id>,
+
+	/// The violation of the constraint, as reported by the database.
+	source: Box<dyn DatabaseError>,
+}
+
+impl DatabaseError for OverlappingTimesheet
+{
+	fn message(&self) -> &str
+	{
+		self.source.message()
+	}
+
+	fn code(&self) -> Option<Cow<'_, str>>
+	{
+		self.source.code()
+	}
+
+	fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static)
+	{
+		self
+	}
+
+	fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static)
+	{
+		self
+	}
+
+	fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static>
+	{
+		self
+	}
+
+	fn constraint(&self) -> Option<&str>
+	{
+		self.source.constraint()
+	}
+}
+
+impl Display for OverlappingTimesheet
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+	{
+		if let Some(id) = self.timesheet_id
+		{
+			write!(f, "Timesheet #{id}")?;
+		}
+		else
+		{
+			write!(f, "The new timesheet")?;
+		}
+
+		write!(f, " of employee #{} overlaps timesheet #{}", self.employee_id, self.conflicting_id)
+	}
+}
+
+impl StdError for OverlappingTimesheet
+{
+	fn source(&self) -> Option<&(dyn StdError + 'static)>
+	{
+		Some(self.source.as_error())
+	}
+}
+
+/// Create the `btree_gist` extension and an exclusion constraint which prevents the
+/// `timesheets` of one employee from overlapping, if they do not already exist.
+pub(super) async fn enable<'connection, Conn>(connection: Conn) -> Result<()>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	let mut transaction = connection.begin().await?;
+
+	(&mut transaction).execute("CREATE EXTENSION IF NOT EXISTS btree_gist;").await?;
+
+	let exists: bool = sqlx::query_scalar(
+		"SELECT EXISTS (SELECT FROM pg_constraint WHERE conrelid = 'timesheets'::regclass AND \
+		 conname = $1);",
+	)
+	.bind(CONSTRAINT)
+	.fetch_one(&mut transaction)
+	.await?;
+
+	if !exists
+	{
+		// NOTE: a timesheet without a `time_end` has not ended yet, so it overlaps everything after
+		//       its `time_begin`.
+		(&mut transaction)
+			.execute(
+				format!(
+					"ALTER TABLE timesheets ADD CONSTRAINT {CONSTRAINT} EXCLUDE USING gist \
+					 (employee_id WITH =, tstzrange(time_begin, time_end) WITH &&);"
+				)
+				.as_str(),
+			)
+			.await?;
+	}
+
+	transaction.commit().await
+}
+
+/// Drop the constraint created by [`enable`]. The `btree_gist` extension is left installed, since
+/// other objects may depend on it.
+pub(super) async fn disable<'connection, Conn>(connection: Conn) -> Result<()>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	let mut transaction = connection.begin().await?;
+
+	(&mut transaction)
+		.execute(format!("ALTER TABLE timesheets DROP CONSTRAINT IF EXISTS {CONSTRAINT};").as_str())
+		.await?;
+
+	transaction.commit().await
+}
+
+/// Find the first of the `timesheets` which overlaps another [`Timesheet`] of the same
+/// [`Employee`](clinvoice_schema::Employee), as if the `timesheets` had been written via the
+/// `connection`.
+///
+/// Returns the `id`, `employee_id`, and the [`Id`] of the [`Timesheet`] which it overlaps.
+///
+/// [`Timesheet`]: clinvoice_schema::Timesheet
+async fn find_conflict<Iter>(
+	connection: &mut Transaction<'_, Postgres>,
+	timesheets: Iter,
+) -> Result<Option<(Option<Id>, Id, Id)>>
+where
+	Iter: IntoIterator<Item = Written>,
+{
+	let mut query = QueryBuilder::new("WITH W (id, employee_id, time_begin, time_end) AS (");
+	query.push_values(timesheets, |mut q, (id, employee_id, time_begin, time_end)| {
+		q.push_bind(id).push_bind(employee_id).push_bind(time_begin).push_bind(time_end);
+	});
+
+	// NOTE: the `timesheets` may overlap each other as well as those already stored.
+	query.push(
+		"),
+		T AS
+		(
+			SELECT id, employee_id, time_begin, time_end FROM timesheets
+			WHERE id NOT IN (SELECT id FROM W WHERE id IS NOT NULL)
+			UNION ALL SELECT id, employee_id, time_begin, time_end FROM W
+		)
+		SELECT W.id, W.employee_id, T.id AS conflicting_id
+		FROM W
+		JOIN T ON
+		(
+			T.employee_id = W.employee_id AND T.id IS DISTINCT FROM W.id AND
+			tstzrange(T.time_begin, T.time_end) && tstzrange(W.time_begin, W.time_end)
+		)
+		ORDER BY W.id, T.id
+		LIMIT 1;",
+	);
+
+	let row = query.prepare().fetch_optional(&mut *connection).await?;
+	row.map(|r| Ok((r.try_get("id")?, r.try_get("employee_id")?, r.try_get("conflicting_id")?)))
+		.transpose()
+}
+
+/// Convert the `error`, which occurred while writing the `timesheets`, into an
+/// [`OverlappingTimesheet`] if it is a violation of the constraint created by [`enable`].
+/// Otherwise, the `error` is returned as-is.
+///
+/// The `timesheets` must have been written in a savepoint of the `connection`, and `rollback` is
+/// the result of rolling that savepoint back. If it could not be rolled back, or what the
+/// `timesheets` overlapped cannot be found, the violation is returned as-is rather than the reason
+/// why, since it is more useful to the caller.
+pub(super) async fn map_err<Iter>(
+	connection: &mut Transaction<'_, Postgres>,
+	rollback: Result<()>,
+	timesheets: Iter,
+	error: Error,
+) -> Error
+where
+	Iter: IntoIterator<Item = Written>,
+{
+	let source = match error
+	{
+		Error::Database(e) if e.constraint() == Some(CONSTRAINT) => e,
+		_ => return error,
+	};
+
+	if rollback.is_err()
+	{
+		return Error::Database(source);
+	}
+
+	match find_conflict(connection, timesheets).await
+	{
+		Ok(Some((timesheet_id, employee_id, conflicting_id))) =>
+		{
+			Error::Database(Box::new(OverlappingTimesheet {
+				conflicting_id,
+				employee_id,
+				timesheet_id,
+				source,
+			}))
+		},
+		Ok(None) | Err(_) => Error::Database(source),
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use core::time::Duration;
+
+	use clinvoice_adapter::{
+		schema::{
+			EmployeeAdapter,
+			JobAdapter,
+			LocationAdapter,
+			OrganizationAdapter,
+			TimesheetAdapter,
+		},
+		Updatable,
+	};
+	use clinvoice_schema::{
+		chrono::{TimeZone, Utc},
+		Currency,
+		Id,
+		Invoice,
+		Money,
+	};
+	use pretty_assertions::assert_eq;
+	use sqlx::{Error, Executor};
+
+	use super::OverlappingTimesheet;
+	use crate::{
+		schema::{
+			migrations::MIGRATIONS,
+			util,
+			PgEmployee,
+			PgJob,
+			PgLocation,
+			PgOrganization,
+			PgTimesheet,
+		},
+		PgSchema,
+	};
+
+	/// The `timesheet_id` and `conflicting_id` of the [`OverlappingTimesheet`] in the `error`.
+	fn overlap(error: &Error) -> (Option<Id>, Id)
+	{
+		let overlap = error
+			.as_database_error()
+			.and_then(|e| e.try_downcast_ref::<OverlappingTimesheet>())
+			.unwrap();
+
+		(overlap.timesheet_id, overlap.conflicting_id)
+	}
+
+	#[tokio::test]
+	async fn enable()
+	{
+		let connection = util::connect().await;
+
+		// NOTE: the constraint is added to a temporary schema which is rolled back, so that the
+		//       other tests may still create overlapping timesheets. The schema is named after the
+		//       connection, so that it cannot collide with another.
+		let mut transaction = connection.begin().await.unwrap();
+		let (schema, search_path): (String, String) = sqlx::query_as(
+			"SELECT 'clinvoice_timesheet_overlap_' || pg_backend_pid(), \
+			 current_setting('search_path');",
+		)
+		.fetch_one(&mut transaction)
+		.await
+		.unwrap();
+
+		(&mut transaction).execute(format!("CREATE SCHEMA {schema};").as_str()).await.unwrap();
+		sqlx::query("SELECT set_config('search_path', $1, true);")
+			.bind(format!("{schema}, {search_path}"))
+			.execute(&mut transaction)
+			.await
+			.unwrap();
+
+		for migration in MIGRATIONS
+		{
+			(&mut transaction).execute(migration.up).await.unwrap();
+		}
+
+		// NOTE: enabling more than once is harmless
+		PgSchema::enable_timesheet_overlap_exclusion(&mut transaction).await.unwrap();
+		PgSchema::enable_timesheet_overlap_exclusion(&mut transaction).await.unwrap();
+
+		let earth = PgLocation::create(&mut transaction, "Earth".into(), None).await.unwrap();
+		let organization =
+			PgOrganization::create(&mut transaction, earth, "Some Organization".into())
+				.await
+				.unwrap();
+
+		let employee = PgEmployee::create(
+			&mut transaction,
+			"My Name".into(),
+			"Employed".into(),
+			"Janitor".into(),
+		)
+		.await
+		.unwrap();
+		let employee2 = PgEmployee::create(
+			&mut transaction,
+			"Another Gúy".into(),
+			"Employed".into(),
+			"Clerk".into(),
+		)
+		.await
+		.unwrap();
+
+		let job = PgJob::create(
+			&mut transaction,
+			organization,
+			None,
+			Utc.ymd(2022, 08, 01).and_hms(08, 00, 00),
+			Duration::from_secs(900),
+			Invoice { date: None, hourly_rate: Money::new(20_00, 2, Currency::Usd) },
+			String::new(),
+			"Clean".into(),
+		)
+		.await
+		.unwrap();
+
+		let first = PgTimesheet::create(
+			&mut transaction,
+			employee.clone(),
+			Vec::new(),
+			job.clone(),
+			Utc.ymd(2022, 08, 01).and_hms(09, 00, 00),
+			Some(Utc.ymd(2022, 08, 01).and_hms(10, 00, 00)),
+			String::new(),
+		)
+		.await
+		.unwrap();
+
+		let error = PgTimesheet::create(
+			&mut transaction,
+			employee.clone(),
+			Vec::new(),
+			job.clone(),
+			Utc.ymd(2022, 08, 01).and_hms(09, 30, 00),
+			Some(Utc.ymd(2022, 08, 01).and_hms(10, 30, 00)),
+			String::new(),
+		)
+		.await
+		.unwrap_err();
+
+		assert_eq!(overlap(&error), (None, first.id));
+
+		// NOTE: the transaction can still be used after an overlap, and timesheets which are
+		//       adjacent, or belong to another employee, do not overlap.
+		PgTimesheet::create(
+			&mut transaction,
+			employee2,
+			Vec::new(),
+			job.clone(),
+			Utc.ymd(2022, 08, 01).and_hms(09, 30, 00),
+			Some(Utc.ymd(2022, 08, 01).and_hms(10, 30, 00)),
+			String::new(),
+		)
+		.await
+		.unwrap();
+
+		let mut second = PgTimesheet::create(
+			&mut transaction,
+			employee.clone(),
+			Vec::new(),
+			job.clone(),
+			Utc.ymd(2022, 08, 01).and_hms(10, 00, 00),
+			Some(Utc.ymd(2022, 08, 01).and_hms(11, 00, 00)),
+			String::new(),
+		)
+		.await
+		.unwrap();
+
+		second.time_begin = Utc.ymd(2022, 08, 01).and_hms(09, 45, 00);
+		let error = PgTimesheet::update(&mut transaction, [&second].into_iter()).await.unwrap_err();
+		assert_eq!(overlap(&error), (Some(second.id), first.id));
+
+		PgSchema::disable_timesheet_overlap_exclusion(&mut transaction).await.unwrap();
+		PgTimesheet::update(&mut transaction, [&second].into_iter()).await.unwrap();
+
+		transaction.rollback().await.unwrap();
+	}
+}
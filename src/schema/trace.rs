@@ -0,0 +1,61 @@
+//! # Summary
+//!
+//! This module records the details of adapter operations on the [`tracing`] span of the operation,
+//! when the `tracing` feature is enabled. Otherwise, it does nothing.
+//!
+//! The arguments of an operation (e.g. the `match_condition`, or the entities being updated) are
+//! never recorded, since they are the values which get bound to queries and may be sensitive.
+//!
+//! [`tracing`]: https://docs.rs/tracing
+
+use core::time::Duration;
+
+/// The field of a span which records the cumulative time, in microseconds, spent retrieving
+/// [`ExchangeRates`](money2::ExchangeRates) from an
+/// [`ExchangeRatesSource`](super::ExchangeRatesSource).
+#[cfg(feature = "tracing")]
+const EXCHANGE_RATES_US: &str = "exchange_rates_us";
+
+/// The field of a span which records how many [`Location`](clinvoice_schema::Location)s were
+/// retrieved alongside the entities of the operation.
+#[cfg(feature = "tracing")]
+const LOCATIONS: &str = "locations";
+
+/// The field of a span which records how many rows were retrieved, or affected, by the operation.
+#[cfg(feature = "tracing")]
+const ROWS: &str = "rows";
+
+/// Record the total `elapsed` time which the current operation has spent retrieving
+/// [`ExchangeRates`](money2::ExchangeRates).
+#[cfg(feature = "tracing")]
+pub(super) fn exchange_rates(elapsed: Duration)
+{
+	tracing::Span::current()
+		.record(EXCHANGE_RATES_US, u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX));
+}
+
+/// Record how many [`Location`](clinvoice_schema::Location)s the current operation has retrieved.
+#[cfg(feature = "tracing")]
+pub(super) fn locations(count: usize)
+{
+	tracing::Span::current().record(LOCATIONS, count);
+}
+
+/// Record how many rows the current operation has retrieved, or affected.
+#[cfg(feature = "tracing")]
+pub(super) fn rows(count: usize)
+{
+	tracing::Span::current().record(ROWS, count);
+}
+
+/// Does nothing, since the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub(super) const fn exchange_rates(_: Duration) {}
+
+/// Does nothing, since the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub(super) const fn locations(_: usize) {}
+
+/// Does nothing, since the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub(super) const fn rows(_: usize) {}
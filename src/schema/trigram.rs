@@ -0,0 +1,108 @@
+use sqlx::{Acquire, Executor, Postgres, Result};
+
+use super::util::TEXT_COLUMNS;
+
+/// The name of the trigram index on the `column` of the `table`.
+fn index_name(table: &str, column: &str) -> String
+{
+	format!("{table}__{column}_trgm_idx")
+}
+
+/// Create the `pg_trgm` extension and a GIN trigram index on every column in [`TEXT_COLUMNS`], if
+/// they do not already exist.
+pub(super) async fn enable<'connection, Conn>(connection: Conn) -> Result<()>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	let mut transaction = connection.begin().await?;
+
+	(&mut transaction).execute("CREATE EXTENSION IF NOT EXISTS pg_trgm;").await?;
+
+	for (table, column) in TEXT_COLUMNS
+	{
+		let index = index_name(table, column);
+		(&mut transaction)
+			.execute(
+				format!(
+					"CREATE INDEX IF NOT EXISTS {index} ON {table} USING gin ({column} \
+					 gin_trgm_ops);"
+				)
+				.as_str(),
+			)
+			.await?;
+	}
+
+	transaction.commit().await
+}
+
+/// Drop every trigram index created by [`enable`]. The `pg_trgm` extension is left installed, since
+/// other objects may depend on it.
+pub(super) async fn disable<'connection, Conn>(connection: Conn) -> Result<()>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	let mut transaction = connection.begin().await?;
+
+	for (table, column) in TEXT_COLUMNS
+	{
+		let index = index_name(table, column);
+		(&mut transaction).execute(format!("DROP INDEX IF EXISTS {index};").as_str()).await?;
+	}
+
+	transaction.commit().await
+}
+
+#[cfg(test)]
+mod tests
+{
+	use clinvoice_adapter::{schema::EmployeeAdapter, Retrievable};
+	use clinvoice_match::{MatchEmployee, MatchStr};
+	use pretty_assertions::assert_eq;
+
+	use super::{index_name, TEXT_COLUMNS};
+	use crate::{
+		schema::{util, PgEmployee},
+		PgSchema,
+	};
+
+	#[tokio::test]
+	async fn enable()
+	{
+		let connection = util::connect().await;
+
+		// NOTE: enabling more than once is harmless
+		PgSchema::enable_trigram_search(&connection).await.unwrap();
+		PgSchema::enable_trigram_search(&connection).await.unwrap();
+
+		let indexes: Vec<String> = sqlx::query_scalar(
+			"SELECT indexname::text FROM pg_indexes
+			WHERE schemaname = current_schema() AND indexname = ANY($1)",
+		)
+		.bind(TEXT_COLUMNS.iter().map(|(t, c)| index_name(t, c)).collect::<Vec<_>>())
+		.fetch_all(&connection)
+		.await
+		.unwrap();
+
+		assert_eq!(indexes.len(), TEXT_COLUMNS.len());
+
+		let employee = PgEmployee::create(
+			&connection,
+			"Trigram Tester".into(),
+			"Employed".into(),
+			"Searcher of substrings".into(),
+		)
+		.await
+		.unwrap();
+
+		assert_eq!(
+			PgEmployee::retrieve(&connection, MatchEmployee {
+				id: employee.id.into(),
+				title: MatchStr::Contains("of substr".into()),
+				..Default::default()
+			})
+			.await
+			.unwrap(),
+			[employee],
+		);
+	}
+}
@@ -1,11 +1,28 @@
 use core::time::Duration;
 use std::io;
 
-use money2::Error as FinanceError;
+use money2::{Decimal, Error as FinanceError, Money};
 use sqlx::{postgres::types::PgInterval, Error, Result};
 #[cfg(test)]
 use {lazy_static::lazy_static, sqlx::PgPool};
 
+/// The `(table, column)` of every text column which is commonly searched with a
+/// [`MatchStr`](clinvoice_match::MatchStr).
+pub(super) const TEXT_COLUMNS: &[(&str, &str)] = &[
+	("contact_information", "email"),
+	("contact_information", "other"),
+	("employees", "name"),
+	("employees", "status"),
+	("employees", "title"),
+	("expenses", "category"),
+	("expenses", "description"),
+	("jobs", "notes"),
+	("jobs", "objectives"),
+	("locations", "name"),
+	("organizations", "name"),
+	("timesheets", "work_notes"),
+];
+
 #[cfg(test)]
 pub(super) async fn connect() -> PgPool
 {
@@ -49,6 +66,12 @@ pub(super) fn duration_from(interval: PgInterval) -> Result<Duration>
 	))
 }
 
+/// Construct [`Money`] from the `amount` and raw `currency` stored in the database.
+pub(super) fn money_from(amount: Decimal, currency: &str) -> Result<Money>
+{
+	Ok(Money { amount, currency: currency.parse().map_err(finance_err_to_sqlx)? })
+}
+
 /// Map some [error](money2::Error) `e` to an [`Error`].
 pub(super) fn finance_err_to_sqlx(e: FinanceError) -> Error
 {
@@ -0,0 +1,393 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{Acquire, Executor, Postgres, Result};
+
+use super::migrations::MIGRATIONS;
+
+/// The prefix of the schema which the expected tables are created in while [`verify`]ing. It is
+/// followed by the process ID of the connection, so that concurrent calls do not collide.
+const EXPECTED_SCHEMA_PREFIX: &str = "clinvoice_verify_";
+
+/// The name and [`ColumnDefinition`] of every column in a table.
+type Columns = HashMap<(String, String), ColumnDefinition>;
+
+/// The definition of every constraint, keyed by its owner and name.
+type Constraints = HashMap<(String, String), String>;
+
+/// The definition of every index, keyed by its table and name.
+type Indexes = HashMap<(String, String), String>;
+
+/// The type of a column, and whether it may be `null`.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ColumnDefinition
+{
+	/// The type of the column, as rendered by `format_type`.
+	pub data_type: String,
+
+	/// Whether or not the column is `NOT NULL`.
+	pub not_null: bool,
+}
+
+/// A column which differs from what [`PgSchema::migrate`](super::PgSchema::migrate) would create.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ColumnDrift
+{
+	/// The actual definition of the column, or [`None`] if it does not exist.
+	pub actual: Option<ColumnDefinition>,
+
+	/// The name of the column.
+	pub column: String,
+
+	/// The expected definition of the column.
+	pub expected: ColumnDefinition,
+
+	/// The name of the table which the column belongs to.
+	pub table: String,
+}
+
+/// A constraint which differs from what [`PgSchema::migrate`](super::PgSchema::migrate) would
+/// create.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ConstraintDrift
+{
+	/// The actual definition of the constraint, or [`None`] if it does not exist.
+	pub actual: Option<String>,
+
+	/// The expected definition of the constraint, as rendered by `pg_get_constraintdef`.
+	pub expected: String,
+
+	/// The name of the constraint (e.g. `jobs__date_integrity`).
+	pub name: String,
+
+	/// The name of the table or domain which the constraint belongs to.
+	pub owner: String,
+}
+
+/// A domain which differs from what [`PgSchema::migrate`](super::PgSchema::migrate) would create.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DomainDrift
+{
+	/// The actual base type of the domain, or [`None`] if it does not exist.
+	pub actual: Option<ColumnDefinition>,
+
+	/// The expected base type of the domain.
+	pub expected: ColumnDefinition,
+
+	/// The name of the domain (e.g. `currency_code`).
+	pub name: String,
+}
+
+/// An index which differs from what [`PgSchema::migrate`](super::PgSchema::migrate) would create.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IndexDrift
+{
+	/// The actual definition of the index, or [`None`] if it does not exist.
+	pub actual: Option<String>,
+
+	/// The expected definition of the index, as rendered by `pg_get_indexdef`.
+	pub expected: String,
+
+	/// The name of the index (e.g. `jobs__date_open_idx`).
+	pub name: String,
+
+	/// The name of the table which the index belongs to.
+	pub table: String,
+}
+
+/// The differences between the schema of a database and the schema which
+/// [`PgSchema::migrate`](super::PgSchema::migrate) would create.
+///
+/// Objects which exist in the database but are not part of the expected schema are not reported.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SchemaDrift
+{
+	/// Columns which are missing or have a different definition.
+	pub columns: Vec<ColumnDrift>,
+
+	/// Constraints which are missing or have a different definition.
+	pub constraints: Vec<ConstraintDrift>,
+
+	/// Domains (e.g. `currency_code`) which are missing or have a different base type.
+	pub domains: Vec<DomainDrift>,
+
+	/// Indexes which are missing or have a different definition.
+	pub indexes: Vec<IndexDrift>,
+
+	/// Tables which do not exist.
+	pub missing_tables: Vec<String>,
+}
+
+impl SchemaDrift
+{
+	/// Whether or not the database matches the expected schema.
+	pub const fn is_empty(&self) -> bool
+	{
+		self.columns.is_empty() &&
+			self.constraints.is_empty() &&
+			self.domains.is_empty() &&
+			self.indexes.is_empty() &&
+			self.missing_tables.is_empty()
+	}
+}
+
+/// Retrieve the name and [`ColumnDefinition`] of every column in the `schema`.
+async fn columns<'connection, Conn>(connection: Conn, schema: &str) -> Result<Columns>
+where
+	Conn: Executor<'connection, Database = Postgres>,
+{
+	let rows: Vec<(String, String, String, bool)> = sqlx::query_as(
+		"SELECT
+			C.relname::text,
+			A.attname::text,
+			replace(format_type(A.atttypid, A.atttypmod), quote_ident(N.nspname) || '.', ''),
+			A.attnotnull
+		FROM pg_attribute A
+		JOIN pg_class C ON (C.oid = A.attrelid)
+		JOIN pg_namespace N ON (N.oid = C.relnamespace)
+		WHERE N.nspname = $1 AND C.relkind IN ('r', 'p') AND A.attnum > 0 AND NOT A.attisdropped;",
+	)
+	.bind(schema)
+	.fetch_all(connection)
+	.await?;
+
+	Ok(rows
+		.into_iter()
+		.map(|(table, column, data_type, not_null)| {
+			((table, column), ColumnDefinition { data_type, not_null })
+		})
+		.collect())
+}
+
+/// Retrieve the definition of every table and domain constraint in the `schema`.
+async fn constraints<'connection, Conn>(connection: Conn, schema: &str) -> Result<Constraints>
+where
+	Conn: Executor<'connection, Database = Postgres>,
+{
+	let rows: Vec<(String, String, String)> = sqlx::query_as(
+		"SELECT
+			coalesce(C.relname, T.typname)::text,
+			K.conname::text,
+			replace(pg_get_constraintdef(K.oid), quote_ident(N.nspname) || '.', '')
+		FROM pg_constraint K
+		JOIN pg_namespace N ON (N.oid = K.connamespace)
+		LEFT JOIN pg_class C ON (C.oid = K.conrelid)
+		LEFT JOIN pg_type T ON (T.oid = K.contypid)
+		WHERE N.nspname = $1;",
+	)
+	.bind(schema)
+	.fetch_all(connection)
+	.await?;
+
+	Ok(rows.into_iter().map(|(owner, name, definition)| ((owner, name), definition)).collect())
+}
+
+/// Retrieve the name and base type of every domain in the `schema`.
+async fn domains<'connection, Conn>(
+	connection: Conn,
+	schema: &str,
+) -> Result<HashMap<String, ColumnDefinition>>
+where
+	Conn: Executor<'connection, Database = Postgres>,
+{
+	let rows: Vec<(String, String, bool)> = sqlx::query_as(
+		"SELECT T.typname::text, format_type(T.typbasetype, T.typtypmod), T.typnotnull
+		FROM pg_type T
+		JOIN pg_namespace N ON (N.oid = T.typnamespace)
+		WHERE N.nspname = $1 AND T.typtype = 'd';",
+	)
+	.bind(schema)
+	.fetch_all(connection)
+	.await?;
+
+	Ok(rows
+		.into_iter()
+		.map(|(name, data_type, not_null)| (name, ColumnDefinition { data_type, not_null }))
+		.collect())
+}
+
+/// Retrieve the definition of every index on a table in the `schema`.
+async fn indexes<'connection, Conn>(connection: Conn, schema: &str) -> Result<Indexes>
+where
+	Conn: Executor<'connection, Database = Postgres>,
+{
+	let rows: Vec<(String, String, String)> = sqlx::query_as(
+		"SELECT
+			T.relname::text,
+			C.relname::text,
+			replace(pg_get_indexdef(I.indexrelid), quote_ident(N.nspname) || '.', '')
+		FROM pg_index I
+		JOIN pg_class C ON (C.oid = I.indexrelid)
+		JOIN pg_class T ON (T.oid = I.indrelid)
+		JOIN pg_namespace N ON (N.oid = T.relnamespace)
+		WHERE N.nspname = $1;",
+	)
+	.bind(schema)
+	.fetch_all(connection)
+	.await?;
+
+	Ok(rows.into_iter().map(|(table, name, definition)| ((table, name), definition)).collect())
+}
+
+/// Retrieve the name of every table in the `schema`.
+async fn tables<'connection, Conn>(connection: Conn, schema: &str) -> Result<HashSet<String>>
+where
+	Conn: Executor<'connection, Database = Postgres>,
+{
+	sqlx::query_scalar(
+		"SELECT C.relname::text
+		FROM pg_class C
+		JOIN pg_namespace N ON (N.oid = C.relnamespace)
+		WHERE N.nspname = $1 AND C.relkind IN ('r', 'p');",
+	)
+	.bind(schema)
+	.fetch_all(connection)
+	.await
+	.map(|names| names.into_iter().collect())
+}
+
+/// Compare the `expected` [`ColumnDefinition`]s to the `actual` ones.
+fn diff_columns(expected: Columns, actual: &Columns) -> Vec<ColumnDrift>
+{
+	let mut drift: Vec<_> = expected
+		.into_iter()
+		.filter_map(|((table, column), definition)| {
+			let actual_definition = actual.get(&(table.clone(), column.clone()));
+			(actual_definition != Some(&definition)).then(|| ColumnDrift {
+				actual: actual_definition.cloned(),
+				column,
+				expected: definition,
+				table,
+			})
+		})
+		.collect();
+
+	drift.sort_by(|lhs, rhs| (&lhs.table, &lhs.column).cmp(&(&rhs.table, &rhs.column)));
+	drift
+}
+
+/// Compare the database's schema to the schema which [`MIGRATIONS`] would create.
+///
+/// The [`MIGRATIONS`] are applied to a temporary schema within a transaction which is rolled back,
+/// so the database is not modified.
+pub(super) async fn verify<'connection, Conn>(connection: Conn) -> Result<SchemaDrift>
+where
+	Conn: Acquire<'connection, Database = Postgres> + Send,
+{
+	let mut transaction = connection.begin().await?;
+
+	let (actual_schema, search_path, expected_schema): (String, String, String) = sqlx::query_as(
+		"SELECT current_schema()::text, current_setting('search_path'), $1 || pg_backend_pid();",
+	)
+	.bind(EXPECTED_SCHEMA_PREFIX)
+	.fetch_one(&mut transaction)
+	.await?;
+
+	(&mut transaction).execute(format!("CREATE SCHEMA {expected_schema};").as_str()).await?;
+
+	// NOTE: putting the expected schema first means that everything created by the migrations goes
+	//       into it, while extensions installed elsewhere are still visible.
+	sqlx::query("SELECT set_config('search_path', $1, true);")
+		.bind(format!("{expected_schema}, {search_path}"))
+		.execute(&mut transaction)
+		.await?;
+
+	for migration in MIGRATIONS
+	{
+		(&mut transaction).execute(migration.up).await?;
+	}
+
+	sqlx::query("SELECT set_config('search_path', $1, true);")
+		.bind(&search_path)
+		.execute(&mut transaction)
+		.await?;
+
+	let actual_tables = tables(&mut transaction, &actual_schema).await?;
+	let mut missing_tables: Vec<_> = tables(&mut transaction, &expected_schema)
+		.await?
+		.into_iter()
+		.filter(|t| !actual_tables.contains(t))
+		.collect();
+	missing_tables.sort();
+
+	let actual_columns = columns(&mut transaction, &actual_schema).await?;
+	let columns = diff_columns(
+		columns(&mut transaction, &expected_schema)
+			.await?
+			.into_iter()
+			// NOTE: the columns of a missing table are already reported by `missing_tables`
+			.filter(|((table, _), _)| !missing_tables.contains(table))
+			.collect(),
+		&actual_columns,
+	);
+
+	let actual_domains = domains(&mut transaction, &actual_schema).await?;
+	let mut domains: Vec<_> = domains(&mut transaction, &expected_schema)
+		.await?
+		.into_iter()
+		.filter_map(|(name, expected)| {
+			let actual = actual_domains.get(&name);
+			(actual != Some(&expected)).then(|| DomainDrift {
+				actual: actual.cloned(),
+				expected,
+				name,
+			})
+		})
+		.collect();
+	domains.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+
+	let actual_constraints = constraints(&mut transaction, &actual_schema).await?;
+	let mut constraints: Vec<_> = constraints(&mut transaction, &expected_schema)
+		.await?
+		.into_iter()
+		.filter(|((owner, _), _)| !missing_tables.contains(owner))
+		.filter_map(|((owner, name), expected)| {
+			let actual = actual_constraints.get(&(owner.clone(), name.clone()));
+			(actual != Some(&expected)).then(|| ConstraintDrift {
+				actual: actual.cloned(),
+				expected,
+				name,
+				owner,
+			})
+		})
+		.collect();
+	constraints.sort_by(|lhs, rhs| (&lhs.owner, &lhs.name).cmp(&(&rhs.owner, &rhs.name)));
+
+	let actual_indexes = indexes(&mut transaction, &actual_schema).await?;
+	let mut indexes: Vec<_> = indexes(&mut transaction, &expected_schema)
+		.await?
+		.into_iter()
+		.filter(|((table, _), _)| !missing_tables.contains(table))
+		.filter_map(|((table, name), expected)| {
+			let actual = actual_indexes.get(&(table.clone(), name.clone()));
+			(actual != Some(&expected)).then(|| IndexDrift {
+				actual: actual.cloned(),
+				expected,
+				name,
+				table,
+			})
+		})
+		.collect();
+	indexes.sort_by(|lhs, rhs| (&lhs.table, &lhs.name).cmp(&(&rhs.table, &rhs.name)));
+
+	transaction.rollback().await?;
+
+	Ok(SchemaDrift { columns, constraints, domains, indexes, missing_tables })
+}
+
+#[cfg(test)]
+mod tests
+{
+	use pretty_assertions::assert_eq;
+
+	use crate::{schema::util, PgSchema};
+
+	#[tokio::test]
+	async fn verify()
+	{
+		let connection = util::connect().await;
+
+		PgSchema::migrate(&connection).await.unwrap();
+
+		assert_eq!(PgSchema::verify(&connection).await.unwrap(), Default::default());
+	}
+}
@@ -27,10 +27,64 @@ use clinvoice_match::{
 	MatchStr,
 	MatchTimesheet,
 };
-use sqlx::{Database, Executor, Postgres, QueryBuilder, Result};
+use money2::Money;
+use sqlx::{
+	postgres::PgHasArrayType,
+	Database,
+	Encode,
+	Executor,
+	Postgres,
+	QueryBuilder,
+	Result,
+	Type,
+};
+
+use super::{
+	expenses::COST_CURRENCY,
+	job::INVOICE_HOURLY_RATE_CURRENCY,
+	PgLocation,
+	PgSchema,
+	StrComparison,
+};
 
-use super::{PgLocation, PgSchema};
-use crate::fmt::{PgInterval, PgTimestampTz};
+/// Implementors of this trait are capable of writing a `WHERE` clause for `Match` conditions, the
+/// same way that [`WriteWhereClause`] does for [`PgSchema`], except that the strings of each
+/// [`MatchStr`] are compared according to `self`.
+pub trait WriteWhere<Match>
+{
+	/// Write the `match_condition` on the `ident` to the `query`, and return the [`WriteContext`]
+	/// which follows it.
+	///
+	/// The rest of the args are the same as [`WriteWhereClause::write_where_clause`].
+	fn write_where<Ident>(
+		self,
+		context: WriteContext,
+		ident: Ident,
+		match_condition: Match,
+		query: &mut QueryBuilder<Postgres>,
+	) -> WriteContext
+	where
+		Ident: Copy + Display;
+}
+
+/// Every `Match` is written by [`StrComparison::Exact`], unless a [`StrComparison`] is given
+/// explicitly via [`WriteWhere`].
+impl<Match> WriteWhereClause<Postgres, Match> for PgSchema
+where
+	StrComparison: WriteWhere<Match>,
+{
+	fn write_where_clause<Ident>(
+		context: WriteContext,
+		ident: Ident,
+		match_condition: Match,
+		query: &mut QueryBuilder<Postgres>,
+	) -> WriteContext
+	where
+		Ident: Copy + Display,
+	{
+		StrComparison::Exact.write_where(context, ident, match_condition, query)
+	}
+}
 
 /// Write [`Match::Any`], [`MatchStr::Any`], [`MatchOption::Any`], or [`MatchSet::Any`] in a way
 /// that will produce valid syntax.
@@ -86,34 +140,68 @@ where
 /// If any the following:
 ///
 /// * `ident` is empty.
-fn write_boolean_group<Db, Ident, Iter, Match, const UNION: bool>(
-	query: &mut QueryBuilder<Db>,
+///
+/// # See also
+///
+/// * [`write_boolean_group_with`], for how `any` is handled.
+fn write_boolean_group<Ident, Match, const UNION: bool>(
+	query: &mut QueryBuilder<Postgres>,
+	comparison: StrComparison,
 	context: WriteContext,
 	ident: Ident,
-	conditions: &mut Iter,
+	conditions: &[Match],
+	any: &Match,
 ) where
 	Ident: Copy + Display,
-	Db: Database,
-	Iter: Iterator<Item = Match>,
-	PgSchema: WriteWhereClause<Db, Match>,
+	Match: PartialEq,
+	for<'m> StrComparison: WriteWhere<&'m Match>,
 {
+	write_boolean_group_with::<_, _, UNION>(query, context, conditions, any, |q, ctx, c| {
+		comparison.write_where(ctx, ident, c, q);
+	});
+}
+
+/// Write multiple `AND`/`OR` `conditions` the same way as [`write_boolean_group`], using `write` to
+/// write each condition in the [`WriteContext`] it is given.
+///
+/// Since `any` (e.g. [`Match::Any`]) matches everything, it is skipped when `UNION` is `true`, and
+/// makes the group match everything when `UNION` is `false`. A group with no other `conditions`
+/// matches everything too.
+fn write_boolean_group_with<Match, F, const UNION: bool>(
+	query: &mut QueryBuilder<Postgres>,
+	context: WriteContext,
+	conditions: &[Match],
+	any: &Match,
+	mut write: F,
+) where
+	Match: PartialEq,
+	F: FnMut(&mut QueryBuilder<Postgres>, WriteContext, &Match),
+{
+	let mut iter = conditions.iter().filter(|c| *c != any).peekable();
+	if (!UNION && conditions.contains(any)) || iter.peek().is_none()
+	{
+		write_any(query, context);
+		return;
+	}
+
 	write_context_scope_start::<_, false>(query, context);
 
-	if let Some(m) = conditions.next()
+	if let Some(c) = iter.next()
 	{
-		PgSchema::write_where_clause(WriteContext::InWhereCondition, ident, m, query);
+		write(query, WriteContext::InWhereCondition, c);
 	}
 
 	let separator = if UNION { sql::AND } else { sql::OR };
-	conditions.for_each(|c| {
+	iter.for_each(|c| {
 		query.push(separator);
-		PgSchema::write_where_clause(WriteContext::InWhereCondition, ident, c, query);
+		write(query, WriteContext::InWhereCondition, c);
 	});
 
 	write_context_scope_end(query);
 }
 
-/// Write a comparison of `ident` and `comparand` using `comparator`.
+/// Write a comparison of `ident` and `comparand` using `comparator`. The `comparand` is sent to
+/// the database as a bind parameter.
 ///
 /// The rest of the args are the same as [`WriteSql::write_where`].
 ///
@@ -122,25 +210,129 @@ fn write_boolean_group<Db, Ident, Iter, Match, const UNION: bool>(
 /// If any the following:
 ///
 /// * `ident` is empty.
-///
-/// # Warnings
-///
-/// * Does not guard against SQL injection.
-fn write_comparison<Db, Ident, Comparand>(
-	query: &mut QueryBuilder<Db>,
+fn write_comparison<Ident, Comparand>(
+	query: &mut QueryBuilder<Postgres>,
 	context: WriteContext,
 	ident: Ident,
 	comparator: &str,
 	comparand: Comparand,
 ) where
-	Db: Database,
 	Ident: Copy + Display,
-	Comparand: Copy + Display,
+	Comparand: for<'args> Encode<'args, Postgres> + Send + Type<Postgres> + 'static,
+{
+	query.separated(' ').push(context).push(ident).push(comparator).push_bind(comparand);
+}
+
+/// Write `{context} {ident} = ANY({values})`. The `values` are sent to the database as a single
+/// array bind parameter, since Postgres accepts no more than 65535 bind parameters per query.
+///
+/// The rest of the args are the same as [`WriteSql::write_where`].
+fn write_equal_to_any<Ident, T>(
+	query: &mut QueryBuilder<Postgres>,
+	context: WriteContext,
+	ident: Ident,
+	values: Vec<T>,
+) where
+	Ident: Copy + Display,
+	T: for<'args> Encode<'args, Postgres> + PgHasArrayType + Send + Type<Postgres> + 'static,
+{
+	query.separated(' ').push(context).push(ident).push("= ANY(");
+	query.push_bind(values).push(')');
+}
+
+/// Write a [`Match`] of [`Money`], where the `amount` and `currency` of the [`Money`] are stored in
+/// separate columns.
+///
+/// An `amount` is only ever compared to [`Money`] of the same `currency` (e.g. `Match::GreaterThan(
+/// 20 USD)` will not match `30 EUR`), so that values are not exchanged unless explicitly requested.
+///
+/// The rest of the args are the same as [`WriteSql::write_where`].
+fn write_match_money<Amount, Currency>(
+	query: &mut QueryBuilder<Postgres>,
+	context: WriteContext,
+	amount: Amount,
+	currency: Currency,
+	match_condition: &Match<Money>,
+) where
+	Amount: Copy + Display,
+	Currency: Copy + Display,
 {
-	query.separated(' ').push(context).push(ident).push(comparator).push(comparand);
+	/// Write `({currency} = {money.currency} AND {amount} {comparator} {money.amount})`.
+	fn write_money_comparison<Amount, Currency>(
+		query: &mut QueryBuilder<Postgres>,
+		context: WriteContext,
+		amount: Amount,
+		currency: Currency,
+		comparator: &str,
+		money: &Money,
+	) where
+		Amount: Display,
+		Currency: Display,
+	{
+		query
+			.push(context)
+			.push(" (")
+			.push(currency)
+			.push('=')
+			.push_bind(money.currency.to_string())
+			.push(sql::AND)
+			.push(amount)
+			.push(' ')
+			.push(comparator)
+			.push(' ')
+			.push_bind(money.amount)
+			.push(')');
+	}
+
+	match match_condition
+	{
+		Match::And(conditions) => write_boolean_group_with::<_, _, true>(
+			query,
+			context,
+			conditions,
+			&Match::Any,
+			|q, ctx, c| write_match_money(q, ctx, amount, currency, c),
+		),
+		Match::Or(conditions) => write_boolean_group_with::<_, _, false>(
+			query,
+			context,
+			conditions,
+			&Match::Any,
+			|q, ctx, c| write_match_money(q, ctx, amount, currency, c),
+		),
+		Match::Any => write_any(query, context),
+		Match::EqualTo(value) =>
+		{
+			write_money_comparison(query, context, amount, currency, "=", value);
+		},
+		Match::GreaterThan(value) =>
+		{
+			write_money_comparison(query, context, amount, currency, ">", value);
+		},
+		Match::InRange(low, high) =>
+		{
+			let ctx = WriteContext::InWhereCondition;
+
+			write_context_scope_start::<_, false>(query, context);
+			write_money_comparison(query, ctx, amount, currency, ">=", low);
+			query.push(sql::AND);
+			write_money_comparison(query, ctx, amount, currency, "<=", high);
+			write_context_scope_end(query);
+		},
+		Match::LessThan(value) =>
+		{
+			write_money_comparison(query, context, amount, currency, "<", value);
+		},
+		Match::Not(condition) =>
+		{
+			write_context_scope_start::<_, true>(query, context);
+			write_match_money(query, WriteContext::InWhereCondition, amount, currency, condition);
+			write_context_scope_end(query);
+		},
+	};
 }
 
-/// An implementation of [`WriteWhereClause`] for [`MatchContact`].
+/// An implementation of [`WriteWhere`] for [`MatchContact`].
 ///
 /// Must be `async` because it involves multiple intermediary database queries to accomplish.
 ///
@@ -152,9 +344,10 @@ fn write_comparison<Db, Ident, Comparand>(
 ///
 /// # See also
 ///
-/// * [`WriteWhereClause::write_where_clause`].
+/// * [`WriteWhere::write_where`].
 pub(super) async fn write_match_contact<'connection, Conn, Ident>(
 	connection: Conn,
+	comparison: StrComparison,
 	context: WriteContext,
 	ident: Ident,
 	match_condition: &MatchContact,
@@ -166,30 +359,31 @@ where
 {
 	let columns = ContactColumns::default().scope(ident);
 
-	let ctx = PgSchema::write_where_clause(context, columns.label, &match_condition.label, query);
+	let ctx = comparison.write_where(context, columns.label, &match_condition.label, query);
 	match match_condition.kind
 	{
 		MatchContactKind::Any => write_any(query, ctx),
 
 		MatchContactKind::Address(ref location) =>
 		{
-			let location_id_query = PgLocation::retrieve_matching_ids(connection, location).await?;
-			PgSchema::write_where_clause(ctx, columns.address_id, &location_id_query, query);
+			let location_id_query =
+				PgLocation::retrieve_matching_ids(connection, location, comparison).await?;
+			comparison.write_where(ctx, columns.address_id, &location_id_query, query);
 		},
 
 		MatchContactKind::Email(ref email_address) =>
 		{
-			PgSchema::write_where_clause(ctx, columns.email, email_address, query);
+			comparison.write_where(ctx, columns.email, email_address, query);
 		},
 
 		MatchContactKind::Other(ref other) =>
 		{
-			PgSchema::write_where_clause(ctx, columns.other, other, query);
+			comparison.write_where(ctx, columns.other, other, query);
 		},
 
 		MatchContactKind::Phone(ref phone_number) =>
 		{
-			PgSchema::write_where_clause(ctx, columns.phone, phone_number, query);
+			comparison.write_where(ctx, columns.phone, phone_number, query);
 		},
 	};
 
@@ -199,28 +393,35 @@ where
 /// Append `"{context} NOT ({match_condition})"` to the `query`.
 ///
 /// The args are the same as [`WriteSql::write_where`].
-fn write_negated<Db, Ident, Match>(
-	query: &mut QueryBuilder<Db>,
+fn write_negated<Ident, Match>(
+	query: &mut QueryBuilder<Postgres>,
+	comparison: StrComparison,
 	context: WriteContext,
 	ident: Ident,
 	match_condition: Match,
 ) where
-	Db: Database,
 	Ident: Copy + Display,
-	PgSchema: WriteWhereClause<Db, Match>,
+	StrComparison: WriteWhere<Match>,
 {
 	write_context_scope_start::<_, true>(query, context);
 
-	PgSchema::write_where_clause(WriteContext::InWhereCondition, ident, match_condition, query);
+	comparison.write_where(WriteContext::InWhereCondition, ident, match_condition, query);
 
 	write_context_scope_end(query);
 }
 
-impl<T> WriteWhereClause<Postgres, &Match<T>> for PgSchema
+impl<T> WriteWhere<&Match<T>> for StrComparison
 where
-	T: Display + PartialEq,
+	T: Clone
+		+ for<'args> Encode<'args, Postgres>
+		+ PartialEq
+		+ PgHasArrayType
+		+ Send
+		+ Type<Postgres>
+		+ 'static,
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &Match<T>,
@@ -231,39 +432,69 @@ where
 	{
 		match match_condition
 		{
-			Match::And(conditions) => write_boolean_group::<_, _, _, _, true>(
+			Match::And(conditions) => write_boolean_group::<_, _, true>(
 				query,
+				self,
 				context,
 				ident,
-				&mut conditions.iter().filter(|m| *m != &Match::Any),
+				conditions,
+				&Match::Any,
 			),
 			Match::Any => write_any(query, context),
-			Match::EqualTo(value) => write_comparison(query, context, ident, "=", value),
-			Match::GreaterThan(value) => write_comparison(query, context, ident, ">", value),
+			Match::EqualTo(value) => write_comparison(query, context, ident, "=", value.clone()),
+			Match::GreaterThan(value) =>
+			{
+				write_comparison(query, context, ident, ">", value.clone());
+			},
 			Match::InRange(low, high) =>
 			{
-				write_comparison(query, context, ident, sql::BETWEEN, low);
-				write_comparison(query, WriteContext::InWhereCondition, "", sql::AND, high);
+				write_comparison(query, context, ident, sql::BETWEEN, low.clone());
+				write_comparison(query, WriteContext::InWhereCondition, "", sql::AND, high.clone());
+			},
+			Match::LessThan(value) => write_comparison(query, context, ident, "<", value.clone()),
+			Match::Not(condition) => write_negated(query, self, context, ident, condition.deref()),
+			Match::Or(conditions) =>
+			{
+				let values = conditions
+					.iter()
+					.map(|m| match m
+					{
+						Match::EqualTo(value) => Some(value.clone()),
+						_ => None,
+					})
+					.collect::<Option<Vec<_>>>();
+
+				match values
+				{
+					Some(v) if !v.is_empty() => write_equal_to_any(query, context, ident, v),
+					_ => write_boolean_group::<_, _, false>(
+						query,
+						self,
+						context,
+						ident,
+						conditions,
+						&Match::Any,
+					),
+				}
 			},
-			Match::LessThan(value) => write_comparison(query, context, ident, "<", value),
-			Match::Not(condition) => write_negated(query, context, ident, condition.deref()),
-			Match::Or(conditions) => write_boolean_group::<_, _, _, _, false>(
-				query,
-				context,
-				ident,
-				&mut conditions.iter().filter(|m| *m != &Match::Any),
-			),
 		};
 
 		WriteContext::AcceptingAnotherWhereCondition
 	}
 }
 
-impl<T> WriteWhereClause<Postgres, &MatchOption<T>> for PgSchema
+impl<T> WriteWhere<&MatchOption<T>> for StrComparison
 where
-	T: Display + PartialEq,
+	T: Clone
+		+ for<'args> Encode<'args, Postgres>
+		+ PartialEq
+		+ PgHasArrayType
+		+ Send
+		+ Type<Postgres>
+		+ 'static,
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchOption<T>,
@@ -274,25 +505,31 @@ where
 	{
 		match match_condition
 		{
-			MatchOption::And(conditions) => write_boolean_group::<_, _, _, _, true>(
+			MatchOption::And(conditions) => write_boolean_group::<_, _, true>(
 				query,
+				self,
 				context,
 				ident,
-				&mut conditions.iter().filter(|m| *m != &MatchOption::Any),
+				conditions,
+				&MatchOption::Any,
 			),
 			MatchOption::Any => write_any(query, context),
-			MatchOption::EqualTo(value) => write_comparison(query, context, ident, "=", value),
+			MatchOption::EqualTo(value) =>
+			{
+				write_comparison(query, context, ident, "=", value.clone());
+			},
 			MatchOption::GreaterThan(value) =>
 			{
-				Self::write_where_clause(context, ident, &Match::GreaterThan(value), query);
+				self.write_where(context, ident, &Match::GreaterThan(value.clone()), query);
 			},
 			MatchOption::InRange(low, high) =>
 			{
-				Self::write_where_clause(context, ident, &Match::InRange(low, high), query);
+				let condition = Match::InRange(low.clone(), high.clone());
+				self.write_where(context, ident, &condition, query);
 			},
 			MatchOption::LessThan(value) =>
 			{
-				Self::write_where_clause(context, ident, &Match::LessThan(value), query);
+				self.write_where(context, ident, &Match::LessThan(value.clone()), query);
 			},
 			MatchOption::None =>
 			{
@@ -303,12 +540,17 @@ where
 					.push_unseparated(sql::IS)
 					.push_unseparated(sql::NULL);
 			},
-			MatchOption::Not(condition) => write_negated(query, context, ident, condition.deref()),
-			MatchOption::Or(conditions) => write_boolean_group::<_, _, _, _, false>(
+			MatchOption::Not(condition) =>
+			{
+				write_negated(query, self, context, ident, condition.deref())
+			},
+			MatchOption::Or(conditions) => write_boolean_group::<_, _, false>(
 				query,
+				self,
 				context,
 				ident,
-				&mut conditions.iter().filter(|m| *m != &MatchOption::Any),
+				conditions,
+				&MatchOption::Any,
 			),
 		};
 
@@ -316,9 +558,10 @@ where
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchSet<MatchExpense>> for PgSchema
+impl WriteWhere<&MatchSet<MatchExpense>> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchSet<MatchExpense>,
@@ -331,29 +574,22 @@ impl WriteWhereClause<Postgres, &MatchSet<MatchExpense>> for PgSchema
 		{
 			MatchSet::Any => write_any(query, context),
 
-			MatchSet::And(conditions) | MatchSet::Or(conditions) =>
-			{
-				write_context_scope_start::<_, false>(query, context);
-
-				let iter = &mut conditions.iter().filter(|m| *m != &MatchSet::Any);
-				if let Some(c) = iter.next()
-				{
-					Self::write_where_clause(WriteContext::InWhereCondition, ident, c, query);
-				}
-
-				let separator = match match_condition
-				{
-					MatchSet::And(_) => sql::AND,
-					_ => sql::OR,
-				};
-
-				conditions.iter().for_each(|c| {
-					query.push(separator);
-					Self::write_where_clause(WriteContext::InWhereCondition, ident, c, query);
-				});
-
-				write_context_scope_end(query);
-			},
+			MatchSet::And(conditions) => write_boolean_group::<_, _, true>(
+				query,
+				self,
+				context,
+				ident,
+				conditions,
+				&MatchSet::Any,
+			),
+			MatchSet::Or(conditions) => write_boolean_group::<_, _, false>(
+				query,
+				self,
+				context,
+				ident,
+				conditions,
+				&MatchSet::Any,
+			),
 
 			MatchSet::Contains(match_expense) =>
 			{
@@ -372,7 +608,7 @@ impl WriteWhereClause<Postgres, &MatchSet<MatchExpense>> for PgSchema
 						COLUMNS.scope(ident).timesheet_id,
 					);
 
-				Self::write_where_clause(
+				self.write_where(
 					WriteContext::AcceptingAnotherWhereCondition,
 					subquery_ident,
 					match_expense,
@@ -381,16 +617,20 @@ impl WriteWhereClause<Postgres, &MatchSet<MatchExpense>> for PgSchema
 
 				query.push(')');
 			},
-			MatchSet::Not(condition) => write_negated(query, context, ident, condition.deref()),
+			MatchSet::Not(condition) =>
+			{
+				write_negated(query, self, context, ident, condition.deref())
+			},
 		};
 
 		WriteContext::AcceptingAnotherWhereCondition
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchStr<String>> for PgSchema
+impl WriteWhere<&MatchStr<String>> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchStr<String>,
@@ -399,52 +639,46 @@ impl WriteWhereClause<Postgres, &MatchStr<String>> for PgSchema
 	where
 		Ident: Copy + Display,
 	{
-		// NOTE: we cannot use certain helpers defined above, as some do not
-		// sanitize `match_condition` and are thus susceptible to SQL injection.
 		match match_condition
 		{
-			MatchStr::And(conditions) => write_boolean_group::<_, _, _, _, true>(
+			MatchStr::And(conditions) => write_boolean_group::<_, _, true>(
 				query,
+				self,
 				context,
 				ident,
-				&mut conditions.iter().filter(|m| *m != &MatchStr::Any),
+				conditions,
+				&MatchStr::Any,
 			),
 			MatchStr::Any => write_any(query, context),
 			MatchStr::Contains(string) =>
 			{
-				query
-					.separated(' ')
-					.push(context)
-					.push(ident)
-					.push(sql::LIKE)
-					// HACK: this is the only way I could think to surround `string` with the syntax
-					//       needed (e.g. `foo LIKE '%o%'`) and still sanitize it.
-					.push_bind(format!("%{string}%"));
+				query.push(context).push(' ');
+
+				// HACK: this is the only way I could think to surround `string` with the syntax
+				//       needed (e.g. `foo LIKE '%o%'`) and still sanitize it.
+				self.push_like(query, ident, format!("%{string}%"));
 			},
 			MatchStr::EqualTo(string) =>
 			{
-				query
-					.separated(' ')
-					.push(context)
-					.push(ident)
-					.push_unseparated('=')
-					.push_bind(string.clone());
+				query.push(context).push(' ');
+				self.push_equal(query, ident, string.clone());
 			},
-			MatchStr::Not(condition) => write_negated(query, context, ident, condition.deref()),
-			MatchStr::Or(conditions) => write_boolean_group::<_, _, _, _, false>(
+			MatchStr::Not(condition) =>
+			{
+				write_negated(query, self, context, ident, condition.deref())
+			},
+			MatchStr::Or(conditions) => write_boolean_group::<_, _, false>(
 				query,
+				self,
 				context,
 				ident,
-				&mut conditions.iter().filter(|m| *m != &MatchStr::Any),
+				conditions,
+				&MatchStr::Any,
 			),
 			MatchStr::Regex(regex) =>
 			{
-				query
-					.separated(' ')
-					.push(context)
-					.push(ident)
-					.push_unseparated('~')
-					.push_bind(regex.clone());
+				query.push(context).push(' ');
+				self.push_regex(query, ident, regex.clone());
 			},
 		};
 
@@ -452,9 +686,10 @@ impl WriteWhereClause<Postgres, &MatchStr<String>> for PgSchema
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchEmployee> for PgSchema
+impl WriteWhere<&MatchEmployee> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchEmployee,
@@ -465,10 +700,10 @@ impl WriteWhereClause<Postgres, &MatchEmployee> for PgSchema
 	{
 		let columns = EmployeeColumns::default().scope(ident);
 
-		Self::write_where_clause(
-			Self::write_where_clause(
-				Self::write_where_clause(
-					Self::write_where_clause(context, columns.id, &match_condition.id, query),
+		self.write_where(
+			self.write_where(
+				self.write_where(
+					self.write_where(context, columns.id, &match_condition.id, query),
 					columns.name,
 					&match_condition.name,
 					query,
@@ -484,9 +719,10 @@ impl WriteWhereClause<Postgres, &MatchEmployee> for PgSchema
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchExpense> for PgSchema
+impl WriteWhere<&MatchExpense> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchExpense,
@@ -496,21 +732,20 @@ impl WriteWhereClause<Postgres, &MatchExpense> for PgSchema
 		Ident: Copy + Display,
 	{
 		let columns = ExpenseColumns::default().scope(ident);
+		let cost_currency = format!("{ident}.{COST_CURRENCY}");
 
-		Self::write_where_clause(
-			Self::write_where_clause(
-				Self::write_where_clause(
-					Self::write_where_clause(
-						Self::write_where_clause(context, columns.id, &match_condition.id, query),
-						columns.category,
-						&match_condition.category,
-						query,
-					),
-					// NOTE: `cost` is stored as text on the DB
-					columns.typecast("numeric").cost,
-					&match_condition.cost.map_ref(|c| c.amount),
-					query,
-				),
+		let ctx = self.write_where(
+			self.write_where(context, columns.id, &match_condition.id, query),
+			columns.category,
+			&match_condition.category,
+			query,
+		);
+
+		write_match_money(query, ctx, columns.cost, cost_currency.as_str(), &match_condition.cost);
+
+		self.write_where(
+			self.write_where(
+				WriteContext::AcceptingAnotherWhereCondition,
 				columns.description,
 				&match_condition.description,
 				query,
@@ -522,9 +757,10 @@ impl WriteWhereClause<Postgres, &MatchExpense> for PgSchema
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchInvoice> for PgSchema
+impl WriteWhere<&MatchInvoice> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchInvoice,
@@ -534,30 +770,36 @@ impl WriteWhereClause<Postgres, &MatchInvoice> for PgSchema
 		Ident: Copy + Display,
 	{
 		let columns = JobColumns::default().scope(ident);
+		let hourly_rate_currency = format!("{ident}.{INVOICE_HOURLY_RATE_CURRENCY}");
 
-		Self::write_where_clause(
-			Self::write_where_clause(
-				Self::write_where_clause(
-					context,
-					columns.invoice_date_issued,
-					&match_condition.date_issued,
-					query,
-				),
-				columns.invoice_date_paid,
-				&match_condition.date_paid,
+		let ctx = self.write_where(
+			self.write_where(
+				context,
+				columns.invoice_date_issued,
+				&match_condition.date_issued,
 				query,
 			),
-			// NOTE: `hourly_rate` is stored as text on the DB
-			columns.typecast("numeric").invoice_hourly_rate,
-			&match_condition.hourly_rate.map_ref(|r| r.amount),
+			columns.invoice_date_paid,
+			&match_condition.date_paid,
 			query,
-		)
+		);
+
+		write_match_money(
+			query,
+			ctx,
+			columns.invoice_hourly_rate,
+			hourly_rate_currency.as_str(),
+			&match_condition.hourly_rate,
+		);
+
+		WriteContext::AcceptingAnotherWhereCondition
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchJob> for PgSchema
+impl WriteWhere<&MatchJob> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchJob,
@@ -568,20 +810,20 @@ impl WriteWhereClause<Postgres, &MatchJob> for PgSchema
 	{
 		let columns = JobColumns::default().scope(ident);
 
-		Self::write_where_clause(
-			Self::write_where_clause(
-				Self::write_where_clause(
-					Self::write_where_clause(
-						Self::write_where_clause(
-							Self::write_where_clause(
-								Self::write_where_clause(
+		self.write_where(
+			self.write_where(
+				self.write_where(
+					self.write_where(
+						self.write_where(
+							self.write_where(
+								self.write_where(
 									context,
 									columns.date_close,
-									&match_condition.date_close.map_ref(|d| PgTimestampTz(*d)),
+									&match_condition.date_close,
 									query,
 								),
 								columns.date_open,
-								&match_condition.date_open.map_ref(|d| PgTimestampTz(*d)),
+								&match_condition.date_open,
 								query,
 							),
 							columns.id,
@@ -589,7 +831,7 @@ impl WriteWhereClause<Postgres, &MatchJob> for PgSchema
 							query,
 						),
 						columns.increment,
-						&match_condition.increment.map_ref(|i| PgInterval(i.into_inner())),
+						&match_condition.increment.map_ref(|i| i.into_inner()),
 						query,
 					),
 					ident,
@@ -607,9 +849,10 @@ impl WriteWhereClause<Postgres, &MatchJob> for PgSchema
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchOrganization> for PgSchema
+impl WriteWhere<&MatchOrganization> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchOrganization,
@@ -620,8 +863,8 @@ impl WriteWhereClause<Postgres, &MatchOrganization> for PgSchema
 	{
 		let columns = OrganizationColumns::default().scope(ident);
 
-		Self::write_where_clause(
-			Self::write_where_clause(context, columns.id, &match_condition.id, query),
+		self.write_where(
+			self.write_where(context, columns.id, &match_condition.id, query),
 			columns.name,
 			&match_condition.name,
 			query,
@@ -629,9 +872,10 @@ impl WriteWhereClause<Postgres, &MatchOrganization> for PgSchema
 	}
 }
 
-impl WriteWhereClause<Postgres, &MatchTimesheet> for PgSchema
+impl WriteWhere<&MatchTimesheet> for StrComparison
 {
-	fn write_where_clause<Ident>(
+	fn write_where<Ident>(
+		self,
 		context: WriteContext,
 		ident: Ident,
 		match_condition: &MatchTimesheet,
@@ -642,16 +886,16 @@ impl WriteWhereClause<Postgres, &MatchTimesheet> for PgSchema
 	{
 		let columns = TimesheetColumns::default().scope(ident);
 
-		Self::write_where_clause(
-			Self::write_where_clause(
-				Self::write_where_clause(
-					Self::write_where_clause(context, columns.id, &match_condition.id, query),
+		self.write_where(
+			self.write_where(
+				self.write_where(
+					self.write_where(context, columns.id, &match_condition.id, query),
 					columns.time_begin,
-					&match_condition.time_begin.map_ref(|d| PgTimestampTz(*d)),
+					&match_condition.time_begin,
 					query,
 				),
 				columns.time_end,
-				&match_condition.time_end.map_ref(|d| PgTimestampTz(*d)),
+				&match_condition.time_end,
 				query,
 			),
 			columns.work_notes,